@@ -0,0 +1,134 @@
+//! Achievements - unlocked from the gameplay events of the local player's rounds
+
+use serde::{Deserialize, Serialize};
+use crate::gameplay::events::GameEvent;
+use crate::gameplay::hole::Hole;
+use crate::gameplay::modes::{GameMode, VictoryResult};
+use crate::gameplay::simulation::Simulation;
+
+/// Holes to eat in one round for `TripleThreat`
+pub const TRIPLE_THREAT_HOLES: u32 = 3;
+/// Buildings to swallow in one round for `Demolition`
+pub const DEMOLITION_BUILDINGS: u32 = 25;
+
+/// Every achievement; new ones go at the end to keep stored profiles readable
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Achievement {
+    TripleThreat,
+    BlackHole,
+    CleanSweep,
+    BareHands,
+    Demolition,
+    TopDog,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 6] = [
+        Achievement::TripleThreat,
+        Achievement::BlackHole,
+        Achievement::CleanSweep,
+        Achievement::BareHands,
+        Achievement::Demolition,
+        Achievement::TopDog,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::TripleThreat => "Triple Threat",
+            Achievement::BlackHole => "Black Hole",
+            Achievement::CleanSweep => "Clean Sweep",
+            Achievement::BareHands => "Bare Hands",
+            Achievement::Demolition => "Demolition",
+            Achievement::TopDog => "Top Dog",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::TripleThreat => "Eat 3 holes in one round",
+            Achievement::BlackHole => "Reach the maximum size",
+            Achievement::CleanSweep => "Consume 100% of the city in Solo",
+            Achievement::BareHands => "Win Battle without dashing",
+            Achievement::Demolition => "Swallow 25 buildings in one round",
+            Achievement::TopDog => "Take the lead of the leaderboard",
+        }
+    }
+}
+
+/// Per-round progress of the local player toward the achievements, saved with the round
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AchievementTracker {
+    player_id: u32,
+    holes_eaten: u32,
+    buildings: u32,
+    dashed: bool,
+}
+
+impl AchievementTracker {
+    pub fn new(player_id: u32) -> Self {
+        Self { player_id, holes_eaten: 0, buildings: 0, dashed: false }
+    }
+
+    /// Feed the events of the last steps and the round they led to; returns the achievements
+    /// earned by them (including ones already unlocked, the profile filters those)
+    pub fn process(&mut self, events: &[GameEvent], sim: &Simulation) -> Vec<Achievement> {
+        let player = sim.player();
+        let mut earned = Vec::new();
+        for event in events {
+            match *event {
+                GameEvent::HoleEliminated { by: Some(by), .. } if by == self.player_id => {
+                    self.holes_eaten += 1;
+                    if self.holes_eaten == TRIPLE_THREAT_HOLES {
+                        earned.push(Achievement::TripleThreat);
+                    }
+                }
+                GameEvent::BuildingSwallowed { hole_id, .. } if hole_id == self.player_id => {
+                    self.buildings += 1;
+                    if self.buildings == DEMOLITION_BUILDINGS {
+                        earned.push(Achievement::Demolition);
+                    }
+                }
+                GameEvent::DashUsed { hole_id } if hole_id == self.player_id => self.dashed = true,
+                GameEvent::RoundEnded { mode, ref result } => match (mode, result) {
+                    (GameMode::Solo, VictoryResult::CityConsumed { percentage }) if *percentage >= 100.0 => {
+                        earned.push(Achievement::CleanSweep);
+                    }
+                    (GameMode::Battle, VictoryResult::PlayerWon) if !self.dashed => earned.push(Achievement::BareHands),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        // Checked on the state rather than on rank changes, a lead held from the start counts too;
+        // every hole starts the same size, so a tie is no lead
+        let mut rivals = sim.holes.iter().filter(|h| h.id != player.id && h.is_alive).peekable();
+        if player.is_alive && rivals.peek().is_some() && rivals.all(|h| h.radius < player.radius) {
+            earned.push(Achievement::TopDog);
+        }
+        if player.is_alive && player.radius >= Hole::MAX_RADIUS {
+            earned.push(Achievement::BlackHole);
+        }
+        earned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::modes::ModeRules;
+    use crate::gameplay::skins::HoleSkin;
+
+    #[test]
+    fn a_lead_held_from_the_start_earns_top_dog() {
+        let rules = ModeRules::new(GameMode::Classic, &Settings::default());
+        let mut sim = Simulation::new(rules, "Ana", HoleSkin::default(), 1);
+        let mut tracker = AchievementTracker::new(sim.player().id);
+        // Everyone starts the same size
+        assert!(!tracker.process(&[], &sim).contains(&Achievement::TopDog));
+
+        // Ahead before any rank could change
+        sim.holes[0].radius += 1.0;
+        assert!(tracker.process(&[], &sim).contains(&Achievement::TopDog));
+    }
+}
@@ -0,0 +1,120 @@
+//! Daily challenge history - best score per day and the current streak
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::storage::{self, StorageError};
+use crate::time::date::Date;
+
+/// Magic bytes at the start of a daily history file
+pub const DAILY_MAGIC: [u8; 4] = *b"HIOD";
+/// Current daily history format version
+pub const DAILY_VERSION: u32 = 1;
+
+/// Best daily challenge score of every day played
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DailyHistory {
+    best: BTreeMap<Date, u32>,
+}
+
+impl DailyHistory {
+    /// Best score of a day, `None` if it was not played
+    pub fn best(&self, date: Date) -> Option<u32> {
+        self.best.get(&date).copied()
+    }
+
+    /// Record a finished daily round, returns true if it beat the day's best
+    pub fn record(&mut self, date: Date, score: u32) -> bool {
+        match self.best.get(&date) {
+            Some(&best) if best >= score => false,
+            _ => {
+                self.best.insert(date, score);
+                true
+            }
+        }
+    }
+
+    /// Consecutive days played up to today; a streak is still alive until today is over
+    pub fn streak(&self, today: Date) -> u32 {
+        let mut day = if self.best.contains_key(&today) { today } else { today.add_days(-1) };
+        let mut streak = 0;
+        while self.best.contains_key(&day) {
+            streak += 1;
+            day = day.add_days(-1);
+        }
+        streak
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        storage::encode_versioned(DAILY_MAGIC, DAILY_VERSION, self)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        storage::decode_versioned(DAILY_MAGIC, DAILY_VERSION, bytes)
+    }
+
+    /// Load the history, empty if the file is missing; an unreadable file is kept as `.bak`
+    pub fn load(path: &Path) -> Self {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(_) => return Self::default(),
+        };
+        match Self::from_bytes(&bytes) {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("Could not load daily history {}: {}", path.display(), e);
+                let _ = std::fs::rename(path, path.with_extension("dat.bak"));
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_bytes())
+    }
+}
+
+/// Location of the daily history file
+pub fn daily_history_path() -> PathBuf {
+    storage::data_dir().join("daily.dat")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TODAY: Date = Date { year: 2026, month: 3, day: 1 };
+
+    #[test]
+    fn only_a_better_score_replaces_the_best_of_the_day() {
+        let mut history = DailyHistory::default();
+        assert_eq!(history.best(TODAY), None);
+        assert!(history.record(TODAY, 300));
+        assert!(!history.record(TODAY, 200));
+        assert!(!history.record(TODAY, 300));
+        assert_eq!(history.best(TODAY), Some(300));
+        assert!(history.record(TODAY, 450));
+        assert_eq!(history.best(TODAY), Some(450));
+        assert_eq!(history.best(TODAY.add_days(-1)), None);
+    }
+
+    #[test]
+    fn streaks_count_back_from_today_or_yesterday() {
+        let mut history = DailyHistory::default();
+        assert_eq!(history.streak(TODAY), 0);
+        // Across the end of February
+        for back in 1..=3 {
+            history.record(TODAY.add_days(-back), 100);
+        }
+        // Today is not played yet, the streak is still alive
+        assert_eq!(history.streak(TODAY), 3);
+        history.record(TODAY, 100);
+        assert_eq!(history.streak(TODAY), 4);
+        // A missed day ends it
+        assert_eq!(history.streak(TODAY.add_days(2)), 0);
+        history.record(TODAY.add_days(2), 100);
+        assert_eq!(history.streak(TODAY.add_days(2)), 1);
+        history.record(TODAY.add_days(-5), 100);
+        assert_eq!(history.streak(TODAY), 4);
+    }
+}
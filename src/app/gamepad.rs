@@ -0,0 +1,134 @@
+//! Gamepad devices - read through the Linux joystick interface (`/dev/input/js*`)
+//!
+//! One background thread per pad keeps the latest `GamepadState` and reopens the device after
+//! it is unplugged. There is no rumble; elsewhere than Linux the pad never connects and the game
+//! plays on the keyboard alone.
+
+use std::sync::{Arc, Mutex};
+use crate::app::input::{GamepadMapper, GamepadState, InputFrame, InputSource};
+
+/// Pads watched, one per split-screen player
+pub const MAX_GAMEPADS: usize = 4;
+
+/// Gamepad polled once per frame through a `GamepadMapper`
+pub struct Gamepad {
+    state: Arc<Mutex<GamepadState>>,
+    pub mapper: GamepadMapper,
+}
+
+impl Gamepad {
+    /// Start watching for gamepad `index`; it is picked up whenever one is plugged in
+    pub fn open(index: usize, deadzone: f32) -> Self {
+        let state = Arc::new(Mutex::new(GamepadState::default()));
+        #[cfg(target_os = "linux")]
+        {
+            let shared = Arc::clone(&state);
+            let spawned = std::thread::Builder::new()
+                .name(format!("gamepad{}", index))
+                .spawn(move || linux::watch(index, shared));
+            if let Err(e) = spawned {
+                eprintln!("Could not start gamepad thread: {}", e);
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = index;
+        Self { state, mapper: GamepadMapper::new(deadzone) }
+    }
+
+    /// Every pad slot, connected or not
+    pub fn open_all(deadzone: f32) -> Vec<Self> {
+        (0..MAX_GAMEPADS).map(|i| Self::open(i, deadzone)).collect()
+    }
+
+    pub fn state(&self) -> GamepadState {
+        self.state.lock().map(|s| *s).unwrap_or_default()
+    }
+}
+
+impl InputSource for Gamepad {
+    fn poll(&mut self) -> InputFrame {
+        let state = self.state();
+        self.mapper.frame(state)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs::File;
+    use std::io::Read;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use macroquad::math::Vec2;
+    use crate::app::input::{GamepadButton, GamepadState};
+
+    /// Wait between looks for a device while none is plugged in
+    const RETRY: Duration = Duration::from_secs(1);
+
+    const JS_EVENT_BUTTON: u8 = 0x01;
+    const JS_EVENT_AXIS: u8 = 0x02;
+    /// Set on the synthetic events describing the initial state
+    const JS_EVENT_INIT: u8 = 0x80;
+
+    /// Open a device, follow its events until it goes away, repeat
+    pub fn watch(index: usize, state: Arc<Mutex<GamepadState>>) {
+        let path = format!("/dev/input/js{}", index);
+        loop {
+            let Ok(mut file) = File::open(&path) else {
+                std::thread::sleep(RETRY);
+                continue;
+            };
+            update(&state, |s| *s = GamepadState { connected: true, ..GamepadState::default() });
+
+            // struct js_event { u32 time; i16 value; u8 type; u8 number; }
+            let mut event = [0u8; 8];
+            while file.read_exact(&mut event).is_ok() {
+                let value = i16::from_ne_bytes([event[4], event[5]]);
+                let kind = event[6] & !JS_EVENT_INIT;
+                let number = event[7];
+                update(&state, |s| apply_event(s, kind, number, value));
+            }
+
+            update(&state, |s| *s = GamepadState::default());
+            std::thread::sleep(RETRY);
+        }
+    }
+
+    fn update(state: &Mutex<GamepadState>, f: impl FnOnce(&mut GamepadState)) {
+        if let Ok(mut s) = state.lock() {
+            f(&mut s);
+        }
+    }
+
+    /// Apply one event, mapped for the Xbox layout the `xpad` driver reports
+    fn apply_event(s: &mut GamepadState, kind: u8, number: u8, value: i16) {
+        let axis = value as f32 / i16::MAX as f32;
+        match (kind, number) {
+            (JS_EVENT_BUTTON, _) => {
+                let button = match number {
+                    0 => GamepadButton::South,
+                    1 => GamepadButton::East,
+                    2 => GamepadButton::West,
+                    3 => GamepadButton::North,
+                    4 => GamepadButton::LeftShoulder,
+                    5 => GamepadButton::RightShoulder,
+                    6 => GamepadButton::Select,
+                    7 => GamepadButton::Start,
+                    _ => return,
+                };
+                s.set(button, value != 0);
+            }
+            (JS_EVENT_AXIS, 0) => s.left_stick = Vec2::new(axis.clamp(-1.0, 1.0), s.left_stick.y),
+            (JS_EVENT_AXIS, 1) => s.left_stick = Vec2::new(s.left_stick.x, axis.clamp(-1.0, 1.0)),
+            // The d-pad comes in as a hat on axes 6 and 7
+            (JS_EVENT_AXIS, 6) => {
+                s.set(GamepadButton::DPadLeft, value < 0);
+                s.set(GamepadButton::DPadRight, value > 0);
+            }
+            (JS_EVENT_AXIS, 7) => {
+                s.set(GamepadButton::DPadUp, value < 0);
+                s.set(GamepadButton::DPadDown, value > 0);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,328 @@
+//! Local high scores - top results per mode, world size and seed.
+//!
+//! Every table is stored as its own checksummed record, so a damaged file only
+//! loses the tables it damaged instead of the whole history.
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::lobby::cycle;
+use crate::app::storage::{self, StorageError};
+use crate::gameplay::modes::GameMode;
+use crate::gameplay::simulation::Simulation;
+use crate::time::date::Date;
+use crate::world::gen::WorldSize;
+
+/// Magic bytes at the start of the high score file
+pub const HIGHSCORE_MAGIC: [u8; 4] = *b"HIOH";
+/// Current high score format version
+pub const HIGHSCORE_VERSION: u32 = 1;
+/// Results kept per table
+pub const TABLE_SIZE: usize = 10;
+
+/// One finished round, named like the `LeaderboardEntry` fields it comes from
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub name: String,
+    /// Final radius
+    pub size: f32,
+    pub rank: usize,
+    pub eliminations: i32,
+    /// Objects eaten
+    pub score: i32,
+    pub city_consumed: f32,
+    pub date: Date,
+}
+
+impl HighScoreEntry {
+    /// Result of one human's hole of a finished round
+    pub fn for_human(sim: &Simulation, idx: usize, date: Date) -> Self {
+        let player = &sim.holes[idx];
+        Self {
+            name: player.name.clone(),
+            size: player.radius,
+            rank: sim.rank_of(idx),
+            eliminations: player.eliminations,
+            score: player.score,
+            city_consumed: sim.world.get_consumption_percentage(),
+            date,
+        }
+    }
+
+    /// Value a table is sorted by: city share in Solo, size elsewhere
+    fn sort_key(&self, mode: GameMode) -> f32 {
+        if mode == GameMode::Solo { self.city_consumed } else { self.size }
+    }
+}
+
+/// What a table is for
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct TableKey {
+    pub mode: GameMode,
+    pub world_size: WorldSize,
+    pub seed: u64,
+}
+
+impl TableKey {
+    /// Table a round of the simulation belongs to
+    pub fn for_simulation(sim: &Simulation) -> Self {
+        Self { mode: sim.mode_rules.mode, world_size: sim.mode_rules.world_size, seed: sim.seed }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HighScoreTable {
+    pub key: TableKey,
+    /// Best first, at most `TABLE_SIZE`
+    pub entries: Vec<HighScoreEntry>,
+}
+
+/// Which results the high score screen shows
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HighScoreFilter {
+    pub mode: GameMode,
+    pub world_size: WorldSize,
+    /// `None` merges every seed
+    pub seed: Option<u64>,
+}
+
+impl Default for HighScoreFilter {
+    fn default() -> Self {
+        Self { mode: GameMode::Classic, world_size: WorldSize::default(), seed: None }
+    }
+}
+
+/// Rows of the filter on the high score screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HighScoreField {
+    Mode,
+    WorldSize,
+    Seed,
+}
+
+impl HighScoreField {
+    pub const ALL: [HighScoreField; 3] = [HighScoreField::Mode, HighScoreField::WorldSize, HighScoreField::Seed];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HighScoreField::Mode => "Mode",
+            HighScoreField::WorldSize => "World Size",
+            HighScoreField::Seed => "Seed",
+        }
+    }
+
+    /// Change the field by a number of steps; the seed cycles through "all" and the recorded seeds
+    pub fn adjust(&self, filter: &mut HighScoreFilter, scores: &HighScores, steps: i32) {
+        match self {
+            HighScoreField::Mode => filter.mode = cycle(&GameMode::ALL, filter.mode, steps),
+            HighScoreField::WorldSize => filter.world_size = cycle(&WorldSize::ALL, filter.world_size, steps),
+            HighScoreField::Seed => {
+                let mut choices = vec![None];
+                choices.extend(scores.seeds(filter.mode, filter.world_size).into_iter().map(Some));
+                filter.seed = cycle(&choices, filter.seed, steps);
+            }
+        }
+        // A seed with no table for the new mode or size would show an empty list
+        if filter.seed.is_some_and(|s| !scores.seeds(filter.mode, filter.world_size).contains(&s)) {
+            filter.seed = None;
+        }
+    }
+
+    /// Value formatted for the high score screen
+    pub fn display(&self, filter: &HighScoreFilter) -> String {
+        match self {
+            HighScoreField::Mode => filter.mode.name().to_string(),
+            HighScoreField::WorldSize => filter.world_size.name().to_string(),
+            HighScoreField::Seed => filter.seed.map_or("ALL".to_string(), |s| s.to_string()),
+        }
+    }
+}
+
+/// Every high score table
+#[derive(Clone, Debug, Default)]
+pub struct HighScores {
+    pub tables: Vec<HighScoreTable>,
+}
+
+impl HighScores {
+    /// Add a result to its table; returns its position (0-based) if it made the top
+    pub fn record(&mut self, key: TableKey, entry: HighScoreEntry) -> Option<usize> {
+        let idx = match self.tables.iter().position(|t| t.key == key) {
+            Some(idx) => idx,
+            None => {
+                self.tables.push(HighScoreTable { key, entries: Vec::new() });
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[idx].entries;
+        let pos = entries.iter().position(|e| entry.sort_key(key.mode) > e.sort_key(key.mode)).unwrap_or(entries.len());
+        if pos >= TABLE_SIZE {
+            return None;
+        }
+        entries.insert(pos, entry);
+        entries.truncate(TABLE_SIZE);
+        Some(pos)
+    }
+
+    /// Seeds that have a table for the filter's mode and world size, ascending
+    pub fn seeds(&self, mode: GameMode, world_size: WorldSize) -> Vec<u64> {
+        let mut seeds: Vec<u64> = self.tables.iter()
+            .filter(|t| t.key.mode == mode && t.key.world_size == world_size)
+            .map(|t| t.key.seed)
+            .collect();
+        seeds.sort_unstable();
+        seeds
+    }
+
+    /// Best results matching a filter with their seed, best first
+    pub fn top(&self, filter: &HighScoreFilter) -> Vec<(u64, &HighScoreEntry)> {
+        let mut entries: Vec<(u64, &HighScoreEntry)> = self.tables.iter()
+            .filter(|t| t.key.mode == filter.mode && t.key.world_size == filter.world_size)
+            .filter(|t| filter.seed.is_none_or(|s| s == t.key.seed))
+            .flat_map(|t| t.entries.iter().map(move |e| (t.key.seed, e)))
+            .collect();
+        entries.sort_by(|a, b| b.1.sort_key(filter.mode).total_cmp(&a.1.sort_key(filter.mode)));
+        entries.truncate(TABLE_SIZE);
+        entries
+    }
+
+    /// Header, then one length- and checksum-prefixed record per table
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&HIGHSCORE_MAGIC);
+        bytes.extend_from_slice(&HIGHSCORE_VERSION.to_le_bytes());
+        for table in &self.tables {
+            let record = bincode::serialize(table).expect("bincode serialization");
+            bytes.extend_from_slice(&(record.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&checksum(&record).to_le_bytes());
+            bytes.extend(record);
+        }
+        bytes
+    }
+
+    /// Decode a high score file. Damaged records are skipped; the flag tells if any were
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, bool), StorageError> {
+        if bytes.len() < 8 || bytes[..4] != HIGHSCORE_MAGIC {
+            return Err(StorageError::BadMagic);
+        }
+        let version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        if version != HIGHSCORE_VERSION {
+            return Err(StorageError::UnsupportedVersion(version));
+        }
+
+        let mut scores = Self::default();
+        let mut damaged = false;
+        let mut rest = &bytes[8..];
+        while !rest.is_empty() {
+            if rest.len() < 8 {
+                damaged = true;
+                break;
+            }
+            let len = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            let sum = u32::from_le_bytes([rest[4], rest[5], rest[6], rest[7]]);
+            // A broken length leaves nothing to resync on, keep what was read so far
+            let Some(record) = rest.get(8..8 + len) else {
+                damaged = true;
+                break;
+            };
+            match bincode::deserialize::<HighScoreTable>(record) {
+                Ok(table) if checksum(record) == sum => scores.tables.push(table),
+                _ => damaged = true,
+            }
+            rest = &rest[8 + len..];
+        }
+        Ok((scores, damaged))
+    }
+
+    /// Load the tables, recovering what can be read from a damaged file (the original is kept as `.bak`)
+    pub fn load(path: &Path) -> Self {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(_) => return Self::default(),
+        };
+        match Self::from_bytes(&bytes) {
+            Ok((scores, false)) => scores,
+            Ok((scores, true)) => {
+                eprintln!("High score file {} is damaged, recovered {} tables", path.display(), scores.tables.len());
+                // Keep the original and write back only what survived
+                let _ = std::fs::copy(path, path.with_extension("dat.bak"));
+                let _ = scores.save(path);
+                scores
+            }
+            Err(e) => {
+                eprintln!("Could not load high scores {}: {}", path.display(), e);
+                let _ = std::fs::rename(path, path.with_extension("dat.bak"));
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_bytes())
+    }
+}
+
+/// FNV-1a over a record
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |h, &b| (h ^ b as u32).wrapping_mul(0x0100_0193))
+}
+
+/// Location of the high score file
+pub fn highscores_path() -> PathBuf {
+    storage::data_dir().join("highscores.dat")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(size: f32) -> HighScoreEntry {
+        HighScoreEntry {
+            name: "Ana".to_string(),
+            size,
+            rank: 1,
+            eliminations: 0,
+            score: 10,
+            city_consumed: 0.0,
+            date: Date { year: 2026, month: 1, day: 2 },
+        }
+    }
+
+    #[test]
+    fn damaged_records_only_lose_their_own_tables() {
+        let mut scores = HighScores::default();
+        for seed in 1..=4 {
+            let key = TableKey { mode: GameMode::Classic, world_size: WorldSize::default(), seed };
+            scores.record(key, entry(30.0 + seed as f32));
+        }
+        let mut bytes = scores.to_bytes();
+        // Start of every record's header
+        let mut starts = Vec::new();
+        let mut at = 8;
+        while at < bytes.len() {
+            starts.push(at);
+            at += 8 + u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+        }
+        assert_eq!(starts.len(), 4);
+
+        // A flipped byte inside the second record, and the last one cut short
+        bytes[starts[1] + 12] ^= 0xff;
+        bytes.truncate(starts[3] + 10);
+
+        let (recovered, damaged) = HighScores::from_bytes(&bytes).expect("header is intact");
+        assert!(damaged);
+        let seeds: Vec<u64> = recovered.tables.iter().map(|t| t.key.seed).collect();
+        assert_eq!(seeds, [1, 3]);
+        assert_eq!(recovered.tables[1].entries[0].size, 33.0);
+
+        // Loading keeps the original aside and writes back what survived
+        let path = std::env::temp_dir().join(format!("holeio-highscores-test-{}.dat", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+        assert_eq!(HighScores::load(&path).tables.len(), 2);
+        assert_eq!(std::fs::read(path.with_extension("dat.bak")).unwrap(), bytes);
+        let (rewritten, damaged) = HighScores::from_bytes(&std::fs::read(&path).unwrap()).unwrap();
+        assert!(!damaged);
+        assert_eq!(rewritten.tables.len(), 2);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("dat.bak"));
+    }
+}
@@ -0,0 +1,333 @@
+//! Player input abstraction - keyboard and gamepad reduced to one per-frame snapshot
+//!
+//! The game only reads `InputFrame`s, so any `InputSource` can drive it: the keyboard, a
+//! gamepad, both merged, or scripted frames in tests.
+
+use macroquad::math::Vec2;
+use serde::{Deserialize, Serialize};
+use crate::gameplay::simulation::HoleInput;
+
+/// Stick deflection ignored around the center when nothing else is configured
+pub const DEFAULT_DEADZONE: f32 = 0.2;
+/// Stick deflection that counts as a menu direction
+const STICK_MENU_PRESS: f32 = 0.6;
+/// Deflection the stick has to fall back under before it can send another menu direction
+const STICK_MENU_RELEASE: f32 = 0.3;
+
+/// Menu navigation pressed this frame
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+    /// Delete a character (Backspace / X)
+    Erase,
+    /// Show or hide the on-screen keyboard (Tab / Y)
+    Keyboard,
+}
+
+/// Everything the game needs from the player's devices for one frame
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputFrame {
+    /// Movement, direction plus length up to 1 as the fraction of full speed
+    pub movement: Vec2,
+    /// Dash pressed this frame
+    pub dash: bool,
+    /// Pause (or resume) pressed this frame
+    pub pause: bool,
+    /// Menu actions pressed this frame
+    pub menu: Vec<MenuAction>,
+    /// Characters typed this frame, in the order they were typed
+    pub text: Vec<char>,
+}
+
+impl InputFrame {
+    pub fn pressed(&self, action: MenuAction) -> bool {
+        self.menu.contains(&action)
+    }
+
+    /// Combine two devices: the stronger movement wins, presses from either count
+    pub fn merge(mut self, other: InputFrame) -> Self {
+        if other.movement.length_squared() > self.movement.length_squared() {
+            self.movement = other.movement;
+        }
+        self.dash |= other.dash;
+        self.pause |= other.pause;
+        for action in other.menu {
+            if !self.menu.contains(&action) {
+                self.menu.push(action);
+            }
+        }
+        self.text.extend(other.text);
+        self
+    }
+
+    /// Simulation input for the player's hole; movement longer than 1 is cut to full speed
+    pub fn hole_input(&self) -> HoleInput {
+        HoleInput { move_dir: self.movement.clamp_length_max(1.0), dash: self.dash }
+    }
+
+    /// +1/-1 for Right/Left pressed this frame, 0 otherwise
+    pub fn horizontal(&self) -> i32 {
+        self.pressed(MenuAction::Right) as i32 - self.pressed(MenuAction::Left) as i32
+    }
+}
+
+/// Anything that can be polled once per frame for player input
+pub trait InputSource {
+    fn poll(&mut self) -> InputFrame;
+}
+
+/// Devices a player steers with when several share the machine
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Binding {
+    /// WASD, Left Shift dashes
+    Wasd,
+    /// Arrow keys, Right Shift dashes
+    Arrows,
+    /// Gamepad by index (0-based)
+    Gamepad(u8),
+}
+
+impl Binding {
+    pub const ALL: [Binding; 6] = [
+        Binding::Wasd,
+        Binding::Arrows,
+        Binding::Gamepad(0),
+        Binding::Gamepad(1),
+        Binding::Gamepad(2),
+        Binding::Gamepad(3),
+    ];
+
+    /// Binding a player slot starts with: the two keyboard halves, then the pads
+    pub fn default_for(slot: usize) -> Self {
+        match slot {
+            0 => Binding::Wasd,
+            1 => Binding::Arrows,
+            n => Binding::Gamepad((n - 2) as u8),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Wasd => "WASD + L-Shift".to_string(),
+            Binding::Arrows => "Arrows + R-Shift".to_string(),
+            Binding::Gamepad(i) => format!("Gamepad {}", i + 1),
+        }
+    }
+}
+
+/// Gamepad buttons, named by position (Xbox layout: South = A, East = B, West = X, North = Y)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftShoulder,
+    RightShoulder,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// Raw state of a gamepad as reported by the device
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GamepadState {
+    pub connected: bool,
+    /// Left stick, -1..1 per axis, y pointing down
+    pub left_stick: Vec2,
+    /// Held buttons, one bit per `GamepadButton`
+    pub buttons: u16,
+}
+
+impl GamepadState {
+    pub fn held(&self, button: GamepadButton) -> bool {
+        self.buttons & (1 << button as u16) != 0
+    }
+
+    pub fn set(&mut self, button: GamepadButton, held: bool) {
+        if held {
+            self.buttons |= 1 << button as u16;
+        } else {
+            self.buttons &= !(1 << button as u16);
+        }
+    }
+}
+
+/// Scaled radial deadzone: nothing inside `deadzone`, then 0-1 over the rest of the stick's travel
+pub fn apply_deadzone(stick: Vec2, deadzone: f32) -> Vec2 {
+    let len = stick.length();
+    if len <= deadzone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((len - deadzone) / (1.0 - deadzone).max(f32::EPSILON)).min(1.0);
+    stick / len * scaled
+}
+
+/// Turns successive gamepad states into input frames: deadzone, button edges, stick-as-d-pad in menus
+#[derive(Clone, Debug)]
+pub struct GamepadMapper {
+    pub deadzone: f32,
+    previous: GamepadState,
+    /// The stick sent a menu direction and has not been released yet
+    stick_latched: bool,
+}
+
+impl Default for GamepadMapper {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEADZONE)
+    }
+}
+
+impl GamepadMapper {
+    pub fn new(deadzone: f32) -> Self {
+        Self { deadzone, previous: GamepadState::default(), stick_latched: false }
+    }
+
+    pub fn frame(&mut self, state: GamepadState) -> InputFrame {
+        let mut frame = InputFrame::default();
+        if !state.connected {
+            // A pad unplugged mid-press must not leave anything held
+            self.previous = GamepadState::default();
+            self.stick_latched = false;
+            return frame;
+        }
+        let pressed = |b: GamepadButton| state.held(b) && !self.previous.held(b);
+
+        // Movement: analog stick, the d-pad at full speed when the stick is idle
+        frame.movement = apply_deadzone(state.left_stick, self.deadzone);
+        if frame.movement == Vec2::ZERO {
+            let x = state.held(GamepadButton::DPadRight) as i32 - state.held(GamepadButton::DPadLeft) as i32;
+            let y = state.held(GamepadButton::DPadDown) as i32 - state.held(GamepadButton::DPadUp) as i32;
+            frame.movement = Vec2::new(x as f32, y as f32).normalize_or_zero();
+        }
+        frame.dash = pressed(GamepadButton::South) || pressed(GamepadButton::RightShoulder);
+        frame.pause = pressed(GamepadButton::Start);
+
+        let buttons = [
+            (GamepadButton::DPadUp, MenuAction::Up),
+            (GamepadButton::DPadDown, MenuAction::Down),
+            (GamepadButton::DPadLeft, MenuAction::Left),
+            (GamepadButton::DPadRight, MenuAction::Right),
+            (GamepadButton::South, MenuAction::Confirm),
+            (GamepadButton::East, MenuAction::Back),
+            (GamepadButton::West, MenuAction::Erase),
+            (GamepadButton::North, MenuAction::Keyboard),
+        ];
+        for (button, action) in buttons {
+            if pressed(button) {
+                frame.menu.push(action);
+            }
+        }
+
+        // The stick steps through menus once per push, along its dominant axis
+        let stick = state.left_stick;
+        let deflection = stick.x.abs().max(stick.y.abs());
+        if !self.stick_latched && deflection >= STICK_MENU_PRESS {
+            self.stick_latched = true;
+            let action = match (stick.x.abs() > stick.y.abs(), stick.x > 0.0, stick.y > 0.0) {
+                (true, true, _) => MenuAction::Right,
+                (true, false, _) => MenuAction::Left,
+                (false, _, true) => MenuAction::Down,
+                (false, _, false) => MenuAction::Up,
+            };
+            if !frame.menu.contains(&action) {
+                frame.menu.push(action);
+            }
+        } else if deflection < STICK_MENU_RELEASE {
+            self.stick_latched = false;
+        }
+
+        self.previous = state;
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::modes::{GameMode, ModeRules};
+    use crate::gameplay::simulation::Simulation;
+    use crate::gameplay::skins::HoleSkin;
+    use crate::time::tick::TICK_DT;
+    use std::collections::VecDeque;
+
+    /// Scripted gamepad for tests: feeds queued states through a `GamepadMapper`
+    struct MockGamepad {
+        mapper: GamepadMapper,
+        states: VecDeque<GamepadState>,
+    }
+
+    impl MockGamepad {
+        fn new(deadzone: f32, states: impl IntoIterator<Item = GamepadState>) -> Self {
+            Self { mapper: GamepadMapper::new(deadzone), states: states.into_iter().collect() }
+        }
+    }
+
+    impl InputSource for MockGamepad {
+        fn poll(&mut self) -> InputFrame {
+            let state = self.states.pop_front().unwrap_or_default();
+            self.mapper.frame(state)
+        }
+    }
+
+    fn pad(stick: Vec2, held: &[GamepadButton]) -> GamepadState {
+        let mut state = GamepadState { connected: true, left_stick: stick, buttons: 0 };
+        for &button in held {
+            state.set(button, true);
+        }
+        state
+    }
+
+    #[test]
+    fn gamepad_presses_fire_once_and_the_stick_respects_the_deadzone() {
+        let states = [
+            pad(Vec2::new(0.1, 0.0), &[GamepadButton::South]),
+            pad(Vec2::new(0.0, 0.8), &[GamepadButton::South]),
+            pad(Vec2::new(0.0, 0.9), &[]),
+        ];
+        let mut source = MockGamepad::new(0.2, states);
+
+        let first = source.poll();
+        assert_eq!(first.movement, Vec2::ZERO);
+        assert!(first.dash && first.pressed(MenuAction::Confirm));
+
+        // Held button and a stick push: no repeat press, one menu step
+        let second = source.poll();
+        assert!(!second.dash && !second.pressed(MenuAction::Confirm));
+        assert!(second.pressed(MenuAction::Down));
+        assert!((second.movement.y - 0.75).abs() < 1e-5);
+
+        // Still pushed, so no second step
+        assert!(!source.poll().pressed(MenuAction::Down));
+        // Unplugged: nothing at all
+        assert_eq!(source.poll(), InputFrame::default());
+    }
+
+    #[test]
+    fn stick_magnitude_scales_hole_speed() {
+        let distance = |stick: Vec2| {
+            let rules = ModeRules::new(GameMode::Solo, &Settings::default());
+            let mut sim = Simulation::new(rules, "P", HoleSkin::default(), 7);
+            sim.start();
+            let start = sim.player().position();
+            let mut source = MockGamepad::new(0.2, std::iter::repeat_n(pad(stick, &[]), 30));
+            for _ in 0..30 {
+                sim.step(&[source.poll().hole_input()], TICK_DT);
+            }
+            sim.player().position().distance(start)
+        };
+        let full = distance(Vec2::new(1.0, 0.0));
+        let half = distance(Vec2::new(0.6, 0.0));
+        assert!(full > 0.0);
+        assert!((half / full - 0.5).abs() < 0.05, "half stick moved {} of {}", half, full);
+    }
+}
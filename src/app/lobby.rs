@@ -0,0 +1,233 @@
+//! Match setup (lobby) - per-match overrides of the round rules
+
+use crate::app::input::Binding;
+use crate::app::settings::Settings;
+use crate::gameplay::bots::BOT_TIER_PRESETS;
+use crate::gameplay::daily::DailyChallenge;
+use crate::gameplay::modes::{GameMode, ModeRules};
+use crate::time::date::Date;
+use crate::world::gen::WorldSize;
+
+/// Longest seed that can be typed (u64::MAX has 20 digits)
+pub const MAX_SEED_DIGITS: usize = 20;
+/// Most humans sharing one screen
+pub const MAX_LOCAL_PLAYERS: usize = 4;
+
+/// Rules for the next match plus the seed to generate it from
+#[derive(Clone, Debug)]
+pub struct MatchSetup {
+    pub rules: ModeRules,
+    /// Typed seed, empty for a random one every match
+    pub seed_text: String,
+    /// Day whose daily challenge this is, the score goes to the daily history
+    pub daily: Option<Date>,
+    /// Humans playing split screen on this machine
+    pub players: usize,
+    /// Devices of each player slot; a lone player uses every device
+    pub bindings: [Binding; MAX_LOCAL_PLAYERS],
+}
+
+impl MatchSetup {
+    /// Setup with the defaults from the player's settings
+    pub fn new(mode: GameMode, settings: &Settings) -> Self {
+        Self {
+            rules: ModeRules::new(mode, settings),
+            seed_text: String::new(),
+            daily: None,
+            players: 1,
+            bindings: std::array::from_fn(Binding::default_for),
+        }
+    }
+
+    /// Setup for a day's challenge: fixed rules and seed
+    pub fn daily(challenge: &DailyChallenge) -> Self {
+        Self {
+            rules: challenge.rules(),
+            seed_text: challenge.seed.to_string(),
+            daily: Some(challenge.date),
+            // Daily scores are personal, the challenge is always played alone
+            players: 1,
+            bindings: std::array::from_fn(Binding::default_for),
+        }
+    }
+
+    /// Bindings of the players in the match
+    pub fn active_bindings(&self) -> &[Binding] {
+        &self.bindings[..self.players]
+    }
+
+    /// Seed for the next match: the typed one, or `random` when none was typed
+    pub fn seed(&self, random: u64) -> u64 {
+        self.seed_text.parse().unwrap_or(random)
+    }
+
+    /// Append a typed character to the seed, ignoring anything but digits
+    pub fn push_seed_char(&mut self, c: char) {
+        if !c.is_ascii_digit() || self.seed_text.len() >= MAX_SEED_DIGITS {
+            return;
+        }
+        let mut text = self.seed_text.clone();
+        text.push(c);
+        // Reject digits that would overflow u64
+        if text.parse::<u64>().is_ok() {
+            self.seed_text = text;
+        }
+    }
+
+    pub fn pop_seed_char(&mut self) {
+        self.seed_text.pop();
+    }
+}
+
+/// One editable entry of the lobby
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LobbyField {
+    Players,
+    /// Devices of a player slot, only shown with more than one player
+    Controls(usize),
+    BotCount,
+    BotDifficulty,
+    RoundDuration,
+    WorldSize,
+    Seed,
+    RespawnTime,
+    GrowthMultiplier,
+}
+
+impl LobbyField {
+    /// Fields shown for a setup, top to bottom
+    pub fn rows(setup: &MatchSetup) -> Vec<LobbyField> {
+        let mut rows = vec![LobbyField::Players];
+        if setup.players > 1 {
+            rows.extend((0..setup.players).map(LobbyField::Controls));
+        }
+        rows.extend([
+            LobbyField::BotCount,
+            LobbyField::BotDifficulty,
+            LobbyField::RoundDuration,
+            LobbyField::WorldSize,
+            LobbyField::Seed,
+            LobbyField::RespawnTime,
+            LobbyField::GrowthMultiplier,
+        ]);
+        rows
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LobbyField::Players => "Players",
+            LobbyField::Controls(0) => "P1 Controls",
+            LobbyField::Controls(1) => "P2 Controls",
+            LobbyField::Controls(2) => "P3 Controls",
+            LobbyField::Controls(_) => "P4 Controls",
+            LobbyField::BotCount => "Bots",
+            LobbyField::BotDifficulty => "Bot Difficulty",
+            LobbyField::RoundDuration => "Round Duration",
+            LobbyField::WorldSize => "World Size",
+            LobbyField::Seed => "Seed",
+            LobbyField::RespawnTime => "Respawn Time",
+            LobbyField::GrowthMultiplier => "Growth",
+        }
+    }
+
+    /// Whether the field means anything in a mode (e.g. no respawn in Battle)
+    pub fn applies_to(&self, mode: GameMode) -> bool {
+        match self {
+            LobbyField::BotCount | LobbyField::BotDifficulty => mode.has_bots(),
+            LobbyField::RespawnTime => mode.allows_respawn(),
+            _ => true,
+        }
+    }
+
+    /// Fraction of the field's range, for drawing a slider
+    pub fn slider(&self, setup: &MatchSetup) -> Option<f32> {
+        let r = &setup.rules;
+        match self {
+            LobbyField::BotCount => Some(r.bot_count as f32 / 15.0),
+            LobbyField::RoundDuration => Some((base_duration(r) - 30.0) / 570.0),
+            LobbyField::RespawnTime => Some((r.respawn_time - 0.5) / 9.5),
+            LobbyField::GrowthMultiplier => Some((r.growth_multiplier - 0.05) / 0.95),
+            _ => None,
+        }
+    }
+
+    /// Change the field by a number of steps; choices wrap, the seed goes back to random
+    pub fn adjust(&self, setup: &mut MatchSetup, steps: i32) {
+        let r = &mut setup.rules;
+        if !self.applies_to(r.mode) {
+            return;
+        }
+        let s = steps as f32;
+        match self {
+            LobbyField::Players => {
+                setup.players = (setup.players as i32 + steps).clamp(1, MAX_LOCAL_PLAYERS as i32) as usize;
+                // A player who just joined gets the first device nobody else holds
+                for slot in 1..setup.players {
+                    if setup.bindings[..slot].contains(&setup.bindings[slot]) {
+                        let free = Binding::ALL.iter().find(|b| !setup.bindings[..slot].contains(b));
+                        setup.bindings[slot] = *free.expect("more bindings than players");
+                    }
+                }
+            }
+            LobbyField::Controls(slot) => {
+                // Step over devices another player already holds
+                let taken = |b: Binding, bindings: &[Binding]| bindings.iter().enumerate().any(|(i, &o)| i != *slot && o == b);
+                let mut binding = setup.bindings[*slot];
+                for _ in 0..steps.unsigned_abs() {
+                    binding = cycle(&Binding::ALL, binding, steps.signum());
+                    while taken(binding, setup.active_bindings()) {
+                        binding = cycle(&Binding::ALL, binding, steps.signum());
+                    }
+                }
+                setup.bindings[*slot] = binding;
+            }
+            LobbyField::BotCount => r.bot_count = (r.bot_count as i32 + steps).clamp(0, 15) as usize,
+            LobbyField::BotDifficulty => {
+                let preset = BOT_TIER_PRESETS.iter().position(|p| *p == r.bot_tiers.as_slice()).unwrap_or(1) as i32;
+                let next = (preset + steps).rem_euclid(BOT_TIER_PRESETS.len() as i32) as usize;
+                r.bot_tiers = BOT_TIER_PRESETS[next].to_vec();
+            }
+            LobbyField::RoundDuration => {
+                // Step through the same base durations as the settings screen
+                r.round_duration = r.mode.round_duration((base_duration(r) + 30.0 * s).clamp(30.0, 600.0));
+            }
+            LobbyField::WorldSize => r.world_size = cycle(&WorldSize::ALL, r.world_size, steps),
+            LobbyField::Seed => setup.seed_text.clear(),
+            LobbyField::RespawnTime => r.respawn_time = ((r.respawn_time + 0.5 * s) * 2.0).round().clamp(1.0, 20.0) / 2.0,
+            LobbyField::GrowthMultiplier => r.growth_multiplier = ((r.growth_multiplier + 0.05 * s) * 20.0).round().clamp(1.0, 20.0) / 20.0,
+        }
+    }
+
+    /// Value formatted for the lobby screen
+    pub fn display(&self, setup: &MatchSetup) -> String {
+        let r = &setup.rules;
+        if !self.applies_to(r.mode) {
+            return "-".to_string();
+        }
+        match self {
+            LobbyField::Players => setup.players.to_string(),
+            LobbyField::Controls(slot) => setup.bindings[*slot].name(),
+            LobbyField::BotCount => r.bot_count.to_string(),
+            LobbyField::BotDifficulty => match r.bot_tiers.as_slice() {
+                [tier] => tier.name().to_string(),
+                _ => "Mixed".to_string(),
+            },
+            LobbyField::RoundDuration => format!("{}:{:02}", r.round_duration as u32 / 60, r.round_duration as u32 % 60),
+            LobbyField::WorldSize => r.world_size.name().to_string(),
+            LobbyField::Seed => if setup.seed_text.is_empty() { "RANDOM".to_string() } else { setup.seed_text.clone() },
+            LobbyField::RespawnTime => format!("{:.1}s", r.respawn_time),
+            LobbyField::GrowthMultiplier => format!("{:.2}x", r.growth_multiplier),
+        }
+    }
+}
+
+/// Round duration before the mode's scaling, rounded to the 30 s steps
+fn base_duration(rules: &ModeRules) -> f32 {
+    (rules.round_duration / rules.mode.round_duration(1.0) / 30.0).round() * 30.0
+}
+
+/// Next/previous entry of a list of choices, wrapping around
+pub(crate) fn cycle<T: Copy + PartialEq>(all: &[T], current: T, steps: i32) -> T {
+    let i = all.iter().position(|v| *v == current).unwrap_or(0) as i32;
+    all[(i + steps).rem_euclid(all.len() as i32) as usize]
+}
@@ -1,2 +1,11 @@
 pub mod state;
+pub mod achievements;
+pub mod daily_history;
+pub mod gamepad;
+pub mod highscores;
+pub mod input;
+pub mod lobby;
+pub mod profile;
 pub mod settings;
+pub mod storage;
+pub mod text_input;
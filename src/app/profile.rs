@@ -0,0 +1,292 @@
+//! Player profile - XP, level and lifetime stats, updated after every round
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::achievements::Achievement;
+use crate::app::storage::{self, StorageError};
+use crate::gameplay::modes::{GameMode, VictoryResult};
+use crate::gameplay::scoring::{calculate_xp, get_medal_for_percentage};
+use crate::gameplay::simulation::Simulation;
+
+/// Magic bytes at the start of a profile file
+pub const PROFILE_MAGIC: [u8; 4] = *b"HIOP";
+/// Current profile format version, bump when `PlayerProfile` changes
+pub const PROFILE_VERSION: u32 = 2;
+
+/// XP needed to go from level 1 to 2; every level after costs this much more
+pub const XP_PER_LEVEL: u64 = 100;
+
+/// Total XP needed to reach a level (level 1 needs none)
+pub fn xp_for_level(level: u32) -> u64 {
+    let l = level.saturating_sub(1) as u64;
+    XP_PER_LEVEL * l * (l + 1) / 2
+}
+
+/// Level reached with a total amount of XP
+pub fn level_for_xp(xp: u64) -> u32 {
+    let mut level = 1;
+    while xp_for_level(level + 1) <= xp {
+        level += 1;
+    }
+    level
+}
+
+/// Progress through the current level, 0-1
+pub fn level_progress(xp: u64) -> f32 {
+    let level = level_for_xp(xp);
+    let start = xp_for_level(level);
+    let end = xp_for_level(level + 1);
+    (xp - start) as f32 / (end - start) as f32
+}
+
+/// Counters kept for each game mode
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct ModeStats {
+    pub matches: u32,
+    pub wins: u32,
+}
+
+/// What the player did in one finished round
+#[derive(Clone, Debug)]
+pub struct MatchSummary {
+    pub mode: GameMode,
+    pub won: bool,
+    /// Largest radius the player reached
+    pub best_size: f32,
+    pub objects_eaten: u32,
+    pub eliminations: u32,
+    pub city_consumed: f32,
+    /// Seconds the player stayed in the round
+    pub time_alive: f32,
+    pub rank: usize,
+    pub total_players: usize,
+}
+
+impl MatchSummary {
+    /// Summary of a finished round from the local player's point of view
+    pub fn from_simulation(sim: &Simulation) -> Self {
+        let player = sim.player();
+        let rank = sim.rank_of(sim.player_idx);
+        let won = match sim.result {
+            // With several humans only the one on top wins
+            VictoryResult::PlayerWon | VictoryResult::TimeUp { .. } => rank == 1,
+            VictoryResult::CityConsumed { percentage } => percentage >= 100.0,
+            _ => false,
+        };
+        let time_alive = match sim.result {
+            VictoryResult::PlayerEliminated { time, .. } => time,
+            _ => sim.clock.elapsed,
+        };
+        Self {
+            mode: sim.mode_rules.mode,
+            won,
+            best_size: player.peak_radius,
+            objects_eaten: player.score.max(0) as u32,
+            eliminations: player.eliminations.max(0) as u32,
+            city_consumed: sim.world.get_consumption_percentage(),
+            time_alive,
+            rank,
+            total_players: sim.holes.len(),
+        }
+    }
+
+    /// XP earned for the round
+    pub fn xp(&self) -> u32 {
+        calculate_xp(self.time_alive, self.objects_eaten as i32, self.eliminations as i32, self.rank, self.total_players).max(0) as u32
+    }
+}
+
+/// XP awarded by one round, kept for the results card animation
+#[derive(Clone, Copy, Debug)]
+pub struct XpGain {
+    /// Total XP before the round
+    pub previous_xp: u64,
+    pub gained: u32,
+}
+
+impl XpGain {
+    pub fn total_xp(&self) -> u64 {
+        self.previous_xp + self.gained as u64
+    }
+}
+
+/// Persistent player profile
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub total_xp: u64,
+    pub classic: ModeStats,
+    pub battle: ModeStats,
+    pub solo: ModeStats,
+    /// Largest radius ever reached
+    pub best_size: f32,
+    pub objects_eaten: u64,
+    pub eliminations: u64,
+    /// Best city percentage consumed in Solo
+    pub best_solo_percentage: f32,
+    /// Unlocked achievements, in unlock order
+    pub achievements: Vec<Achievement>,
+}
+
+/// Version 1 layout, from before achievements
+#[derive(Deserialize)]
+struct ProfileV1 {
+    total_xp: u64,
+    classic: ModeStats,
+    battle: ModeStats,
+    solo: ModeStats,
+    best_size: f32,
+    objects_eaten: u64,
+    eliminations: u64,
+    best_solo_percentage: f32,
+}
+
+impl From<ProfileV1> for PlayerProfile {
+    fn from(v1: ProfileV1) -> Self {
+        Self {
+            total_xp: v1.total_xp,
+            classic: v1.classic,
+            battle: v1.battle,
+            solo: v1.solo,
+            best_size: v1.best_size,
+            objects_eaten: v1.objects_eaten,
+            eliminations: v1.eliminations,
+            best_solo_percentage: v1.best_solo_percentage,
+            achievements: Vec::new(),
+        }
+    }
+}
+
+impl PlayerProfile {
+    pub fn level(&self) -> u32 {
+        level_for_xp(self.total_xp)
+    }
+
+    pub fn mode_stats(&self, mode: GameMode) -> &ModeStats {
+        match mode {
+            GameMode::Classic => &self.classic,
+            GameMode::Battle => &self.battle,
+            GameMode::Solo => &self.solo,
+        }
+    }
+
+    fn mode_stats_mut(&mut self, mode: GameMode) -> &mut ModeStats {
+        match mode {
+            GameMode::Classic => &mut self.classic,
+            GameMode::Battle => &mut self.battle,
+            GameMode::Solo => &mut self.solo,
+        }
+    }
+
+    pub fn matches_played(&self) -> u32 {
+        self.classic.matches + self.battle.matches + self.solo.matches
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.classic.wins + self.battle.wins + self.solo.wins
+    }
+
+    /// Medal for the best Solo round
+    pub fn solo_medal(&self) -> &'static str {
+        get_medal_for_percentage(self.best_solo_percentage)
+    }
+
+    pub fn has_achievement(&self, achievement: Achievement) -> bool {
+        self.achievements.contains(&achievement)
+    }
+
+    /// Record an achievement, returns false if it was already unlocked
+    pub fn unlock_achievement(&mut self, achievement: Achievement) -> bool {
+        if self.has_achievement(achievement) {
+            return false;
+        }
+        self.achievements.push(achievement);
+        true
+    }
+
+    /// Add a finished round to the lifetime stats and award its XP
+    pub fn record_match(&mut self, summary: &MatchSummary) -> XpGain {
+        let gain = XpGain { previous_xp: self.total_xp, gained: summary.xp() };
+        self.total_xp = gain.total_xp();
+
+        let stats = self.mode_stats_mut(summary.mode);
+        stats.matches += 1;
+        if summary.won {
+            stats.wins += 1;
+        }
+        self.best_size = self.best_size.max(summary.best_size);
+        self.objects_eaten += summary.objects_eaten as u64;
+        self.eliminations += summary.eliminations as u64;
+        if summary.mode == GameMode::Solo {
+            self.best_solo_percentage = self.best_solo_percentage.max(summary.city_consumed);
+        }
+        gain
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        storage::encode_versioned(PROFILE_MAGIC, PROFILE_VERSION, self)
+    }
+
+    /// Decode a profile, upgrading older versions
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        match storage::decode_versioned(PROFILE_MAGIC, PROFILE_VERSION, bytes) {
+            Err(StorageError::UnsupportedVersion(1)) => {
+                storage::decode_versioned::<ProfileV1>(PROFILE_MAGIC, 1, bytes).map(Into::into)
+            }
+            result => result,
+        }
+    }
+
+    /// Load the profile, starting a fresh one if the file is missing.
+    /// An unreadable file is kept next to it as `.bak` instead of being overwritten.
+    pub fn load(path: &Path) -> Self {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(_) => return Self::default(),
+        };
+        match Self::from_bytes(&bytes) {
+            Ok(profile) => profile,
+            Err(e) => {
+                eprintln!("Could not load profile {}: {}", path.display(), e);
+                let _ = std::fs::rename(path, path.with_extension("dat.bak"));
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_bytes())
+    }
+}
+
+/// Location of the profile file
+pub fn profile_path() -> PathBuf {
+    storage::data_dir().join("profile.dat")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_1_profiles_are_upgraded() {
+        let classic = ModeStats { matches: 12, wins: 5 };
+        let battle = ModeStats { matches: 3, wins: 1 };
+        // Same field order as `ProfileV1`, bincode stores structs as their fields in a row
+        let v1 = (1234u64, classic, battle, ModeStats::default(), 88.5f32, 400u64, 7u64, 62.0f32);
+        let bytes = storage::encode_versioned(PROFILE_MAGIC, 1, &v1);
+
+        let profile = PlayerProfile::from_bytes(&bytes).expect("version 1 decodes");
+        assert_eq!(profile.total_xp, 1234);
+        assert_eq!(profile.matches_played(), 15);
+        assert_eq!(profile.wins(), 6);
+        assert_eq!(profile.best_size, 88.5);
+        assert_eq!(profile.objects_eaten, 400);
+        assert_eq!(profile.eliminations, 7);
+        assert_eq!(profile.best_solo_percentage, 62.0);
+        assert!(profile.achievements.is_empty());
+
+        // Saved again as the current version
+        let again = PlayerProfile::from_bytes(&profile.to_bytes()).unwrap();
+        assert_eq!(again.total_xp, 1234);
+    }
+}
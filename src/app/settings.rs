@@ -1,9 +1,18 @@
 //! Game settings and configuration
 
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::storage::{self, StorageError};
+use crate::gameplay::skins::HoleSkin;
+
+/// Settings file schema version, bump (and migrate in `Settings::load`) on incompatible changes
+pub const SETTINGS_VERSION: u32 = 1;
+
 /// Game settings
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
-    /// Movement speed multiplier
+    /// Base movement speed in world units per second
     pub move_speed: f32,
     /// Dash cooldown in seconds
     pub dash_cooldown: f32,
@@ -19,12 +28,18 @@ pub struct Settings {
     pub round_duration: f32,
     /// Show FPS counter
     pub show_fps: bool,
-    /// Theme index (0 = default, 1 = dark, 2 = neon)
+    /// Theme index (0 = city, 1 = neon, 2 = sunset)
     pub theme_index: usize,
     /// Screen shake intensity (0-1)
     pub screen_shake_intensity: f32,
     /// Particle density (0-1)
     pub particle_density: f32,
+    /// Gamepad stick deflection (0-1) ignored around the center
+    pub gamepad_deadzone: f32,
+    /// Skin picked on the customization screen
+    pub skin: HoleSkin,
+    /// Name of the player's hole, empty until the first-run prompt was answered
+    pub player_name: String,
 }
 
 impl Default for Settings {
@@ -41,15 +56,259 @@ impl Default for Settings {
             theme_index: 0,
             screen_shake_intensity: 0.5,
             particle_density: 1.0,
+            gamepad_deadzone: 0.2,
+            skin: HoleSkin::default(),
+            player_name: String::new(),
+        }
+    }
+}
+
+/// One editable entry of the settings screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SettingId {
+    MoveSpeed,
+    DashCooldown,
+    DashDuration,
+    DashSpeedMult,
+    CameraSmoothing,
+    BotCount,
+    RoundDuration,
+    ShowFps,
+    Theme,
+    ScreenShake,
+    ParticleDensity,
+    GamepadDeadzone,
+}
+
+/// How a setting is edited
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SettingKind {
+    Slider { min: f32, max: f32, step: f32 },
+    Toggle,
+    /// Pick one of the named options (stored as an index)
+    Choice(&'static [&'static str]),
+}
+
+/// Names of the selectable palettes, in `Theme::set_palette_index` order
+pub const THEME_NAMES: [&str; 3] = ["City", "Neon", "Sunset"];
+
+impl SettingId {
+    pub const ALL: [SettingId; 12] = [
+        SettingId::MoveSpeed,
+        SettingId::DashCooldown,
+        SettingId::DashDuration,
+        SettingId::DashSpeedMult,
+        SettingId::CameraSmoothing,
+        SettingId::BotCount,
+        SettingId::RoundDuration,
+        SettingId::ShowFps,
+        SettingId::Theme,
+        SettingId::ScreenShake,
+        SettingId::ParticleDensity,
+        SettingId::GamepadDeadzone,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingId::MoveSpeed => "Move Speed",
+            SettingId::DashCooldown => "Dash Cooldown",
+            SettingId::DashDuration => "Dash Duration",
+            SettingId::DashSpeedMult => "Dash Speed",
+            SettingId::CameraSmoothing => "Camera Smoothing",
+            SettingId::BotCount => "Bots",
+            SettingId::RoundDuration => "Round Duration",
+            SettingId::ShowFps => "Show FPS",
+            SettingId::Theme => "Theme",
+            SettingId::ScreenShake => "Screen Shake",
+            SettingId::ParticleDensity => "Particles",
+            SettingId::GamepadDeadzone => "Stick Deadzone",
+        }
+    }
+
+    pub fn kind(&self) -> SettingKind {
+        match self {
+            SettingId::MoveSpeed => SettingKind::Slider { min: 100.0, max: 400.0, step: 10.0 },
+            SettingId::DashCooldown => SettingKind::Slider { min: 0.5, max: 10.0, step: 0.5 },
+            SettingId::DashDuration => SettingKind::Slider { min: 0.1, max: 1.0, step: 0.05 },
+            SettingId::DashSpeedMult => SettingKind::Slider { min: 1.0, max: 5.0, step: 0.25 },
+            SettingId::CameraSmoothing => SettingKind::Slider { min: 0.02, max: 1.0, step: 0.02 },
+            SettingId::BotCount => SettingKind::Slider { min: 0.0, max: 15.0, step: 1.0 },
+            SettingId::RoundDuration => SettingKind::Slider { min: 30.0, max: 600.0, step: 30.0 },
+            SettingId::ShowFps => SettingKind::Toggle,
+            SettingId::Theme => SettingKind::Choice(&THEME_NAMES),
+            SettingId::ScreenShake => SettingKind::Slider { min: 0.0, max: 1.0, step: 0.1 },
+            SettingId::ParticleDensity => SettingKind::Slider { min: 0.0, max: 1.0, step: 0.1 },
+            SettingId::GamepadDeadzone => SettingKind::Slider { min: 0.05, max: 0.5, step: 0.05 },
+        }
+    }
+}
+
+impl Settings {
+    /// Current value of a setting as a number (toggles are 0/1, choices their index)
+    pub fn get(&self, id: SettingId) -> f32 {
+        match id {
+            SettingId::MoveSpeed => self.move_speed,
+            SettingId::DashCooldown => self.dash_cooldown,
+            SettingId::DashDuration => self.dash_duration,
+            SettingId::DashSpeedMult => self.dash_speed_mult,
+            SettingId::CameraSmoothing => self.camera_smoothing,
+            SettingId::BotCount => self.bot_count as f32,
+            SettingId::RoundDuration => self.round_duration,
+            SettingId::ShowFps => if self.show_fps { 1.0 } else { 0.0 },
+            SettingId::Theme => self.theme_index as f32,
+            SettingId::ScreenShake => self.screen_shake_intensity,
+            SettingId::ParticleDensity => self.particle_density,
+            SettingId::GamepadDeadzone => self.gamepad_deadzone,
+        }
+    }
+
+    /// Set a setting from a number, clamped to its range
+    pub fn set(&mut self, id: SettingId, value: f32) {
+        let value = match id.kind() {
+            SettingKind::Slider { min, max, step } => ((value / step).round() * step).clamp(min, max),
+            SettingKind::Toggle => if value >= 0.5 { 1.0 } else { 0.0 },
+            SettingKind::Choice(options) => value.round().clamp(0.0, (options.len() - 1) as f32),
+        };
+        self.store(id, value);
+    }
+
+    /// Bring every setting back into the range the settings screen allows, keeping values that
+    /// are in range as they are; a value that is not a number goes back to its default
+    pub fn clamp_to_ranges(&mut self) {
+        let defaults = Settings::default();
+        for id in SettingId::ALL {
+            let value = self.get(id);
+            let value = match id.kind() {
+                _ if !value.is_finite() => defaults.get(id),
+                SettingKind::Slider { min, max, .. } => value.clamp(min, max),
+                SettingKind::Toggle => value,
+                SettingKind::Choice(options) => value.clamp(0.0, (options.len() - 1) as f32),
+            };
+            self.store(id, value);
+        }
+    }
+
+    fn store(&mut self, id: SettingId, value: f32) {
+        match id {
+            SettingId::MoveSpeed => self.move_speed = value,
+            SettingId::DashCooldown => self.dash_cooldown = value,
+            SettingId::DashDuration => self.dash_duration = value,
+            SettingId::DashSpeedMult => self.dash_speed_mult = value,
+            SettingId::CameraSmoothing => self.camera_smoothing = value,
+            SettingId::BotCount => self.bot_count = value as usize,
+            SettingId::RoundDuration => self.round_duration = value,
+            SettingId::ShowFps => self.show_fps = value > 0.5,
+            SettingId::Theme => self.theme_index = value as usize,
+            SettingId::ScreenShake => self.screen_shake_intensity = value,
+            SettingId::ParticleDensity => self.particle_density = value,
+            SettingId::GamepadDeadzone => self.gamepad_deadzone = value,
+        }
+    }
+
+    /// Move a setting by a number of steps (toggles flip, choices wrap around)
+    pub fn adjust(&mut self, id: SettingId, steps: i32) {
+        let value = self.get(id);
+        match id.kind() {
+            SettingKind::Slider { step, .. } => self.set(id, value + step * steps as f32),
+            SettingKind::Toggle => self.set(id, 1.0 - value),
+            SettingKind::Choice(options) => {
+                let n = options.len() as i32;
+                self.set(id, (value as i32 + steps).rem_euclid(n) as f32);
+            }
+        }
+    }
+
+    /// Value formatted for the settings screen
+    pub fn display(&self, id: SettingId) -> String {
+        match id {
+            SettingId::ShowFps => if self.show_fps { "ON".to_string() } else { "OFF".to_string() },
+            SettingId::Theme => THEME_NAMES.get(self.theme_index).copied().unwrap_or("?").to_string(),
+            SettingId::BotCount => self.bot_count.to_string(),
+            SettingId::MoveSpeed => format!("{:.0}", self.move_speed),
+            SettingId::RoundDuration => format!("{}:{:02}", self.round_duration as u32 / 60, self.round_duration as u32 % 60),
+            SettingId::DashCooldown | SettingId::DashDuration => format!("{:.2}s", self.get(id)),
+            SettingId::DashSpeedMult => format!("{:.2}x", self.dash_speed_mult),
+            SettingId::CameraSmoothing | SettingId::ScreenShake | SettingId::ParticleDensity | SettingId::GamepadDeadzone => format!("{:.0}%", self.get(id) * 100.0),
         }
     }
 }
 
+/// On-disk layout of the settings file
+#[derive(Serialize, Deserialize)]
+struct SettingsFile {
+    version: u32,
+    settings: Settings,
+}
+
+/// Location of the settings file (e.g. `~/.config/holeio_modern/settings.toml`)
+pub fn settings_path() -> PathBuf {
+    storage::config_dir().join("settings.toml")
+}
+
 impl Settings {
-    /// Get effective movement speed based on hole size
-    pub fn get_move_speed(&self, hole_radius: f32) -> f32 {
-        // Larger holes move slightly slower
-        let size_penalty = (hole_radius / 50.0).min(1.5);
-        self.move_speed / (1.0 + size_penalty * 0.3)
+    /// Load settings, falling back to defaults when the file is missing, unreadable or from an
+    /// unknown version. A broken file is moved aside to `settings.toml.bak` rather than lost.
+    pub fn load(path: &Path) -> Self {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => return Self::default(),
+        };
+        match toml::from_str::<SettingsFile>(&text) {
+            Ok(file) if file.version == SETTINGS_VERSION => {
+                // Hand-edited files skip the sliders' limits
+                let mut settings = file.settings;
+                settings.clamp_to_ranges();
+                settings
+            }
+            Ok(file) => {
+                eprintln!("Settings file {} has unknown version {}, using defaults", path.display(), file.version);
+                let _ = std::fs::rename(path, path.with_extension("toml.bak"));
+                Self::default()
+            }
+            Err(e) => {
+                eprintln!("Could not parse settings {}: {}", path.display(), e);
+                let _ = std::fs::rename(path, path.with_extension("toml.bak"));
+                Self::default()
+            }
+        }
+    }
+
+    /// Write settings as TOML
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        let file = SettingsFile { version: SETTINGS_VERSION, settings: self.clone() };
+        let text = toml::to_string_pretty(&file).map_err(|e| StorageError::Corrupt(e.to_string()))?;
+        storage::write_file(path, text.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaded_settings_are_clamped_and_broken_files_kept_aside() {
+        let dir = std::env::temp_dir().join(format!("holeio-settings-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.toml");
+
+        let text = "version = 1\n\n[settings]\nbot_count = 100000\nround_duration = 0.0\ncamera_smoothing = 5.0\nmove_speed = 250.0\ntheme_index = 9\n";
+        std::fs::write(&path, text).unwrap();
+        let settings = Settings::load(&path);
+        assert_eq!(settings.bot_count, 15);
+        assert_eq!(settings.round_duration, 30.0);
+        assert_eq!(settings.camera_smoothing, 1.0);
+        assert_eq!(settings.move_speed, 250.0);
+        assert_eq!(settings.theme_index, THEME_NAMES.len() - 1);
+
+        std::fs::write(&path, "version = 1\n[settings\nbot_count = ").unwrap();
+        assert_eq!(Settings::load(&path).bot_count, Settings::default().bot_count);
+        assert!(!path.exists());
+        assert!(path.with_extension("toml.bak").exists());
+
+        std::fs::write(&path, "version = 99\n\n[settings]\nbot_count = 3\n").unwrap();
+        assert_eq!(Settings::load(&path).bot_count, Settings::default().bot_count);
+        assert!(!path.exists());
+        assert_eq!(std::fs::read_to_string(path.with_extension("toml.bak")).unwrap(), "version = 99\n\n[settings]\nbot_count = 3\n");
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
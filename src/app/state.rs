@@ -1,20 +1,65 @@
 //! Game state management - Menu/Playing/Pause/Results
 
+use crate::app::highscores::HighScoreFilter;
+use crate::app::lobby::MatchSetup;
+use crate::app::settings::Settings;
+use crate::app::text_input::{DEFAULT_NAME, OnScreenKeyboard, TextInput};
 use crate::gameplay::modes::GameMode;
+use crate::gameplay::skins::HoleSkin;
 
 /// Main game states
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
 pub enum GameState {
+    #[default]
     Menu,
     ModeSelect,
+    /// Match setup between mode select and the round
+    Lobby,
+    /// Today's daily challenge and the streak calendar
+    Daily,
     Playing,
     Pause,
     Results,
+    /// Watching a recorded round
+    Replay,
+    /// Editing settings
+    Settings,
+    /// Player level and lifetime stats
+    Profile,
+    /// Picking the hole skin
+    Customize,
+    /// Local high score tables
+    HighScores,
+    /// Typing the player name (first run, or from the profile)
+    NameEntry,
+    /// Connected to a LAN server: waiting in its lobby or playing its rounds
+    Network,
 }
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::Menu
+/// Entries of the main menu
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MenuItem {
+    /// Resume a round saved with "Save & Quit"
+    Continue,
+    Play,
+    Profile,
+    HighScores,
+    Customize,
+    Settings,
+    Quit,
+}
+
+impl MenuItem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuItem::Continue => "CONTINUE",
+            MenuItem::Play => "PLAY",
+            MenuItem::Profile => "PROFILE",
+            MenuItem::HighScores => "HIGH SCORES",
+            MenuItem::Customize => "CUSTOMIZE",
+            MenuItem::Settings => "SETTINGS",
+            MenuItem::Quit => "QUIT",
+        }
     }
 }
 
@@ -23,10 +68,32 @@ pub struct AppState {
     pub game_state: GameState,
     pub selected_mode: GameMode,
     pub player_name: String,
+    /// Skin the player's hole is created with (always unlocked)
+    pub player_skin: HoleSkin,
     pub menu_selection: usize,
     pub mode_selection: usize,
     pub pause_selection: usize,
     pub results_selection: usize,
+    pub settings_selection: usize,
+    pub lobby_selection: usize,
+    pub customize_selection: usize,
+    pub daily_selection: usize,
+    pub highscores_selection: usize,
+    pub profile_selection: usize,
+    /// Which table the high score screen shows, kept between visits
+    pub highscore_filter: HighScoreFilter,
+    /// Skin shown on the customization screen, may include locked entries
+    pub customize_skin: HoleSkin,
+    /// Name being typed on the name entry screen
+    pub name_input: TextInput,
+    /// On-screen keyboard, `None` while hidden
+    pub keyboard: Option<OnScreenKeyboard>,
+    /// Screen the name entry goes back to
+    pub name_entry_return: GameState,
+    /// Rules and seed of the current/next match, reused by "restart" and "play again"
+    pub match_setup: MatchSetup,
+    /// A saved round is waiting to be continued
+    pub has_saved_session: bool,
 }
 
 impl Default for AppState {
@@ -34,11 +101,25 @@ impl Default for AppState {
         Self {
             game_state: GameState::Menu,
             selected_mode: GameMode::Classic,
-            player_name: "Player".to_string(),
+            player_name: DEFAULT_NAME.to_string(),
+            player_skin: HoleSkin::default(),
             menu_selection: 0,
             mode_selection: 0,
             pause_selection: 0,
             results_selection: 0,
+            settings_selection: 0,
+            lobby_selection: 0,
+            customize_selection: 0,
+            daily_selection: 0,
+            highscores_selection: 0,
+            profile_selection: 0,
+            highscore_filter: HighScoreFilter::default(),
+            customize_skin: HoleSkin::default(),
+            name_input: TextInput::name(DEFAULT_NAME),
+            keyboard: None,
+            name_entry_return: GameState::Menu,
+            match_setup: MatchSetup::new(GameMode::Classic, &Settings::default()),
+            has_saved_session: false,
         }
     }
 }
@@ -52,10 +133,35 @@ impl AppState {
             GameState::ModeSelect => self.mode_selection = 0,
             GameState::Pause => self.pause_selection = 0,
             GameState::Results => self.results_selection = 0,
+            GameState::Settings => self.settings_selection = 0,
+            GameState::Lobby => self.lobby_selection = 0,
+            GameState::Customize => self.customize_selection = 0,
+            GameState::Daily => self.daily_selection = 0,
+            GameState::HighScores => self.highscores_selection = 0,
+            // "BACK" is preselected so ENTER still just leaves the screen
+            GameState::Profile => self.profile_selection = 1,
             _ => {}
         }
     }
 
+    /// Open the name entry on the current name; it returns to `return_to` when done
+    pub fn edit_name(&mut self, return_to: GameState) {
+        self.name_input = TextInput::name(&self.player_name);
+        self.keyboard = None;
+        self.name_entry_return = return_to;
+        self.transition_to(GameState::NameEntry);
+    }
+
+    /// Main menu entries for the current state
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        let mut items = Vec::new();
+        if self.has_saved_session {
+            items.push(MenuItem::Continue);
+        }
+        items.extend([MenuItem::Play, MenuItem::Profile, MenuItem::HighScores, MenuItem::Customize, MenuItem::Settings, MenuItem::Quit]);
+        items
+    }
+
     pub fn start_game(&mut self, mode: GameMode) {
         self.selected_mode = mode;
         self.game_state = GameState::Playing;
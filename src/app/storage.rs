@@ -0,0 +1,107 @@
+//! Local storage - file locations and versioned binary files
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use macroquad::prelude::{Color, Rect};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Directory name used under the platform config/data dirs
+pub const APP_DIR: &str = "holeio_modern";
+
+/// Error while reading or writing a stored file
+#[derive(Debug)]
+pub enum StorageError {
+    Io(io::Error),
+    /// File does not start with the expected magic bytes
+    BadMagic,
+    /// File was written by a newer (or unknown) format version
+    UnsupportedVersion(u32),
+    /// Payload could not be decoded
+    Corrupt(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "I/O error: {}", e),
+            StorageError::BadMagic => write!(f, "not a recognized file"),
+            StorageError::UnsupportedVersion(v) => write!(f, "unsupported format version {}", v),
+            StorageError::Corrupt(e) => write!(f, "corrupt file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(e: io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+/// Per-user data directory (e.g. `~/.local/share/holeio_modern`)
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR)
+}
+
+/// Per-user config directory (e.g. `~/.config/holeio_modern`)
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or_else(|| PathBuf::from(".")).join(APP_DIR)
+}
+
+/// Encode a value behind a 4-byte magic and a little-endian format version
+pub fn encode_versioned<T: Serialize>(magic: [u8; 4], version: u32, value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(64);
+    bytes.extend_from_slice(&magic);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    // Serializing plain data into a Vec cannot fail
+    bytes.extend(bincode::serialize(value).expect("bincode serialization"));
+    bytes
+}
+
+/// Decode a value written by `encode_versioned`, rejecting other magics and newer versions
+pub fn decode_versioned<T: DeserializeOwned>(magic: [u8; 4], version: u32, bytes: &[u8]) -> Result<T, StorageError> {
+    if bytes.len() < 8 || bytes[..4] != magic {
+        return Err(StorageError::BadMagic);
+    }
+    let file_version = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    if file_version != version {
+        return Err(StorageError::UnsupportedVersion(file_version));
+    }
+    bincode::deserialize(&bytes[8..]).map_err(|e| StorageError::Corrupt(e.to_string()))
+}
+
+/// Write a file, creating parent directories as needed.
+/// Goes through a temporary file so a crash never leaves a half-written file behind.
+pub fn write_file(path: &Path, bytes: &[u8]) -> Result<(), StorageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Serde mirror of macroquad's `Color`, use with `#[serde(with = "ColorDef")]`
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Color")]
+pub struct ColorDef {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+/// Serde mirror of macroquad's `Rect`, use with `#[serde(with = "RectDef")]`
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Rect")]
+pub struct RectDef {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
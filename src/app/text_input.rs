@@ -0,0 +1,283 @@
+//! Single-line text entry - caret editing plus an on-screen keyboard for when there is no keyboard
+
+/// Longest player name, short enough for the HUD leaderboard and hole labels
+pub const MAX_NAME_LEN: usize = 12;
+/// Name used until the player picks one
+pub const DEFAULT_NAME: &str = "Player";
+
+/// Characters a name may contain, all drawable by the default font
+pub fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.')
+}
+
+/// Text being edited, with a caret counted in characters
+#[derive(Clone, Debug)]
+pub struct TextInput {
+    text: String,
+    caret: usize,
+    max_len: usize,
+    allowed: fn(char) -> bool,
+}
+
+impl TextInput {
+    /// Input holding `initial` (filtered and cut to `max_len`), caret at the end
+    pub fn new(initial: &str, max_len: usize, allowed: fn(char) -> bool) -> Self {
+        let text: String = initial.chars().filter(|&c| allowed(c)).take(max_len).collect();
+        let caret = text.chars().count();
+        Self { text, caret, max_len, allowed }
+    }
+
+    /// Input for a player name
+    pub fn name(initial: &str) -> Self {
+        Self::new(initial, MAX_NAME_LEN, is_name_char)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.max_len
+    }
+
+    /// Insert at the caret; returns false for filtered characters or when full
+    pub fn insert(&mut self, c: char) -> bool {
+        if !(self.allowed)(c) || self.is_full() {
+            return false;
+        }
+        let at = self.byte_index(self.caret);
+        self.text.insert(at, c);
+        self.caret += 1;
+        true
+    }
+
+    /// Remove the character before the caret
+    pub fn backspace(&mut self) {
+        if self.caret > 0 {
+            self.caret -= 1;
+            let at = self.byte_index(self.caret);
+            self.text.remove(at);
+        }
+    }
+
+    /// Remove the character after the caret
+    pub fn delete(&mut self) {
+        if self.caret < self.len() {
+            let at = self.byte_index(self.caret);
+            self.text.remove(at);
+        }
+    }
+
+    /// Move the caret by a number of characters, clamped to the text
+    pub fn move_caret(&mut self, steps: i32) {
+        self.caret = (self.caret as i32 + steps).clamp(0, self.len() as i32) as usize;
+    }
+
+    pub fn home(&mut self) {
+        self.caret = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.caret = self.len();
+    }
+
+    /// The text without leading/trailing spaces, `None` if nothing is left
+    pub fn value(&self) -> Option<String> {
+        let trimmed = self.text.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text.char_indices().nth(chars).map_or(self.text.len(), |(i, _)| i)
+    }
+}
+
+/// One key of the on-screen keyboard
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OskKey {
+    Char(char),
+    Shift,
+    Space,
+    Backspace,
+    Done,
+}
+
+impl OskKey {
+    pub fn label(&self, shift: bool) -> String {
+        match self {
+            OskKey::Char(c) if shift => c.to_ascii_uppercase().to_string(),
+            OskKey::Char(c) => c.to_string(),
+            OskKey::Shift => "SHIFT".to_string(),
+            OskKey::Space => "SPACE".to_string(),
+            OskKey::Backspace => "DEL".to_string(),
+            OskKey::Done => "DONE".to_string(),
+        }
+    }
+}
+
+/// Character rows of the on-screen keyboard; the last row holds the special keys
+const OSK_CHAR_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl-", "zxcvbnm_."];
+const OSK_LAST_ROW: [OskKey; 4] = [OskKey::Shift, OskKey::Space, OskKey::Backspace, OskKey::Done];
+
+/// Key grid navigated with arrows or a d-pad, for entering text without typing
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OnScreenKeyboard {
+    pub row: usize,
+    pub col: usize,
+    /// Letters are typed upper case
+    pub shift: bool,
+}
+
+impl OnScreenKeyboard {
+    pub fn rows() -> usize {
+        OSK_CHAR_ROWS.len() + 1
+    }
+
+    /// Keys of a row, left to right
+    pub fn row_keys(row: usize) -> Vec<OskKey> {
+        match OSK_CHAR_ROWS.get(row) {
+            Some(chars) => chars.chars().map(OskKey::Char).collect(),
+            None => OSK_LAST_ROW.to_vec(),
+        }
+    }
+
+    pub fn selected(&self) -> OskKey {
+        Self::row_keys(self.row)[self.col]
+    }
+
+    /// Move the selection; columns wrap within a row, rows wrap top to bottom
+    pub fn move_by(&mut self, dx: i32, dy: i32) {
+        if dy != 0 {
+            // Keep roughly the same horizontal position across rows of different widths
+            let from = Self::row_keys(self.row).len();
+            self.row = (self.row as i32 + dy).rem_euclid(Self::rows() as i32) as usize;
+            let to = Self::row_keys(self.row).len();
+            self.col = (self.col * to / from).min(to - 1);
+        }
+        let len = Self::row_keys(self.row).len() as i32;
+        self.col = (self.col as i32 + dx).rem_euclid(len) as usize;
+    }
+
+    /// Apply the selected key to `input`; returns true when DONE was pressed
+    pub fn press(&mut self, input: &mut TextInput) -> bool {
+        match self.selected() {
+            OskKey::Char(c) => {
+                input.insert(if self.shift { c.to_ascii_uppercase() } else { c });
+                // Shift applies to one letter, like a phone keyboard
+                self.shift = false;
+            }
+            OskKey::Shift => self.shift = !self.shift,
+            OskKey::Space => { input.insert(' '); }
+            OskKey::Backspace => input.backspace(),
+            OskKey::Done => return true,
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_filtered_and_cut_to_length() {
+        let mut input = TextInput::name("Ana!Maria*Silva Santos");
+        assert_eq!(input.text(), "AnaMariaSilv");
+        assert!(input.is_full());
+        assert!(!input.insert('x'));
+
+        input.home();
+        input.delete();
+        assert!(!input.insert('#'));
+        assert!(input.insert('B'));
+        assert_eq!(input.text(), "BnaMariaSilv");
+        assert_eq!(input.caret(), 1);
+    }
+
+    #[test]
+    fn caret_edits_count_characters_not_bytes() {
+        let mut input = TextInput::new("né", 8, |_| true);
+        assert_eq!(input.caret(), 2);
+        input.move_caret(-1);
+        assert!(input.insert('ø'));
+        assert_eq!(input.text(), "nøé");
+        input.end();
+        input.backspace();
+        assert_eq!(input.text(), "nø");
+        input.move_caret(-5);
+        assert_eq!(input.caret(), 0);
+        input.move_caret(9);
+        assert_eq!(input.caret(), 2);
+    }
+
+    #[test]
+    fn erasing_at_the_edges_does_nothing() {
+        let mut input = TextInput::name("ab");
+        input.delete();
+        assert_eq!(input.text(), "ab");
+        input.home();
+        input.backspace();
+        assert_eq!(input.text(), "ab");
+        input.delete();
+        assert_eq!(input.text(), "b");
+        assert_eq!(input.caret(), 0);
+    }
+
+    #[test]
+    fn value_is_trimmed_and_blank_text_has_none() {
+        assert_eq!(TextInput::name("  Bo b ").value().as_deref(), Some("Bo b"));
+        assert_eq!(TextInput::name("   ").value(), None);
+        assert!(TextInput::name("").is_empty());
+    }
+
+    #[test]
+    fn keyboard_selection_wraps_and_keeps_its_place_across_rows() {
+        let mut osk = OnScreenKeyboard::default();
+        osk.move_by(-1, 0);
+        assert_eq!(osk.selected(), OskKey::Char('0'));
+        osk.move_by(0, -1);
+        assert_eq!(osk.row, OnScreenKeyboard::rows() - 1);
+        assert_eq!(osk.selected(), OskKey::Done);
+        osk.move_by(0, 1);
+        assert_eq!((osk.row, osk.selected()), (0, OskKey::Char('8')));
+        osk.move_by(3, 0);
+        assert_eq!(osk.selected(), OskKey::Char('1'));
+    }
+
+    #[test]
+    fn keyboard_keys_edit_the_input() {
+        let mut input = TextInput::name("");
+        let mut osk = OnScreenKeyboard { row: 4, col: 0, shift: false };
+        assert!(!osk.press(&mut input));
+        assert!(osk.shift);
+
+        // Shift makes one upper case letter
+        osk.row = 1;
+        osk.press(&mut input);
+        osk.press(&mut input);
+        assert_eq!(input.text(), "Qq");
+        assert!(!osk.shift);
+
+        osk.row = 4;
+        osk.col = 1;
+        osk.press(&mut input);
+        osk.col = 2;
+        osk.press(&mut input);
+        osk.press(&mut input);
+        assert_eq!(input.text(), "Q");
+        osk.col = 3;
+        assert!(osk.press(&mut input));
+    }
+}
@@ -0,0 +1,134 @@
+//! Hole.io dedicated server - LAN rounds without a window
+//!
+//! Runs only the simulation and the network code: macroquad comes along for its math types,
+//! but no window or graphics context is ever created, so it runs on machines without a display.
+//! Every finished round goes to stdout as one JSON line; status messages go to stderr.
+
+use std::io::Write;
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use ::rand::Rng;
+use holeio_modern::app::settings::Settings;
+use holeio_modern::gameplay::modes::{GameMode, ModeRules};
+use holeio_modern::net::protocol::DEFAULT_PORT;
+use holeio_modern::net::server::{RoundSummary, Server, ServerConfig};
+use holeio_modern::time::tick::TICK_RATE;
+
+const USAGE: &str = "\
+Usage: holeio-server [options]
+
+  --mode NAME         Classic, Battle or Solo (default Classic)
+  --seed N            seed of the first round, then counting up (default random)
+  --tick-rate HZ      simulation ticks per second (default 60)
+  --max-players N     players accepted at once (default 8)
+  --min-players N     players needed to start a round (default 1)
+  --bot-fill N        holes per round, empty ones taken by bots; 0 for no bots (default max players)
+  --port N            UDP port (default 7777)
+  --help              show this help";
+
+/// Highest tick rate accepted, the simulation gains nothing beyond it
+const MAX_TICK_RATE: u32 = 240;
+
+/// Command line options
+struct Options {
+    mode: GameMode,
+    seed: Option<u64>,
+    tick_rate: u32,
+    max_players: usize,
+    min_players: usize,
+    bot_fill: Option<usize>,
+    port: u16,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            mode: GameMode::Classic,
+            seed: None,
+            tick_rate: TICK_RATE,
+            max_players: 8,
+            min_players: 1,
+            bot_fill: None,
+            port: DEFAULT_PORT,
+        };
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+            match flag.as_str() {
+                "--mode" => {
+                    let name = value()?;
+                    options.mode = GameMode::from_name(name).ok_or_else(|| format!("Unknown mode {}", name))?;
+                }
+                "--seed" => options.seed = Some(number(flag, value()?)?),
+                "--tick-rate" => options.tick_rate = number(flag, value()?)?,
+                "--max-players" => options.max_players = number(flag, value()?)?,
+                "--min-players" => options.min_players = number(flag, value()?)?,
+                "--bot-fill" => options.bot_fill = Some(number(flag, value()?)?),
+                "--port" => options.port = number(flag, value()?)?,
+                other => return Err(format!("Unknown option {}", other)),
+            }
+        }
+        if options.tick_rate == 0 || options.tick_rate > MAX_TICK_RATE {
+            return Err(format!("--tick-rate must be 1-{}", MAX_TICK_RATE));
+        }
+        if options.max_players == 0 || options.min_players > options.max_players {
+            return Err("--min-players must be at most --max-players, which must be at least 1".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} expects a number, got {}", flag, value))
+}
+
+/// One JSON line per round on stdout; a closed pipe must not take the server down
+fn log_round(summary: &RoundSummary) {
+    let line = match serde_json::to_string(summary) {
+        Ok(line) => line,
+        Err(e) => {
+            eprintln!("Could not encode round {}: {}", summary.round, e);
+            return;
+        }
+    };
+    let mut out = std::io::stdout().lock();
+    if let Err(e) = writeln!(out, "{}", line).and_then(|_| out.flush()) {
+        eprintln!("Could not write the round log: {}", e);
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let seed = options.seed.unwrap_or_else(|| ::rand::thread_rng().gen());
+    let mut config = ServerConfig::new(ModeRules::new(options.mode, &Settings::default()), seed);
+    config.tick_rate = options.tick_rate;
+    config.max_players = options.max_players;
+    config.min_players = options.min_players;
+    config.bot_fill = Some(options.bot_fill.unwrap_or(options.max_players));
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], options.port));
+    let server = match Server::bind(addr, config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Could not listen on {}: {}", addr, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!(
+        "Serving {} on {} - seed {}, {} Hz, up to {} players",
+        options.mode.name(), addr, seed, options.tick_rate, options.max_players,
+    );
+    server.run(log_round)
+}
@@ -0,0 +1,142 @@
+//! Hole.io bot tournament - seeded rounds of bots only, as fast as the machine allows
+//!
+//! Pits bot tunings against each other over many rounds and reports how each one did, with
+//! 95% confidence intervals, as CSV or JSON. Progress goes to stderr.
+
+use std::process::ExitCode;
+use std::time::Instant;
+use holeio_modern::app::settings::Settings;
+use holeio_modern::gameplay::bots::BotDifficulty;
+use holeio_modern::gameplay::modes::{GameMode, ModeRules};
+use holeio_modern::gameplay::tournament::{Entrant, Tournament, TournamentReport, PROFILE_KEYS};
+
+const USAGE: &str = "\
+Usage: holeio-tournament [options]
+
+  --rounds N          rounds to play (default 100)
+  --seed N            seed of the first round, then counting up (default 1)
+  --mode NAME         Classic or Battle (default Classic)
+  --bots N            bots per round (default 8)
+  --duration SECS     round length (default the mode's)
+  --entrant SPEC      a bot tuning, repeat for each; [NAME=]TIER[:key=value,...]
+                      e.g. bold=Normal:aggression=0.9,threat_margin=1.5 (default one per tier)
+  --format FORMAT     csv or json (default csv)
+  --output PATH       write the report there instead of stdout
+  --jobs N            threads (default one per core)
+  --help              show this help";
+
+/// Command line options
+struct Options {
+    rounds: u32,
+    seed: u64,
+    mode: GameMode,
+    bots: usize,
+    duration: Option<f32>,
+    entrants: Vec<Entrant>,
+    json: bool,
+    output: Option<String>,
+    jobs: usize,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            rounds: 100,
+            seed: 1,
+            mode: GameMode::Classic,
+            bots: 8,
+            duration: None,
+            entrants: Vec::new(),
+            json: false,
+            output: None,
+            jobs: std::thread::available_parallelism().map_or(1, |n| n.get()),
+        };
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+            match flag.as_str() {
+                "--rounds" => options.rounds = number(flag, value()?)?,
+                "--seed" => options.seed = number(flag, value()?)?,
+                "--mode" => {
+                    let name = value()?;
+                    options.mode = GameMode::from_name(name).ok_or_else(|| format!("Unknown mode {}", name))?;
+                }
+                "--bots" => options.bots = number(flag, value()?)?,
+                "--duration" => options.duration = Some(number(flag, value()?)?),
+                "--entrant" => options.entrants.push(Entrant::parse(value()?)?),
+                "--format" => options.json = match value()?.as_str() {
+                    "csv" => false,
+                    "json" => true,
+                    other => return Err(format!("Unknown format {}", other)),
+                },
+                "--output" => options.output = Some(value()?.clone()),
+                "--jobs" => options.jobs = number(flag, value()?)?,
+                other => return Err(format!("Unknown option {}", other)),
+            }
+        }
+        if !options.mode.has_bots() {
+            return Err(format!("{} has no bots", options.mode.name()));
+        }
+        if options.entrants.is_empty() {
+            options.entrants = BotDifficulty::ALL.into_iter().map(Entrant::new).collect();
+        }
+        if options.bots < 2 || options.bots < options.entrants.len() {
+            return Err("--bots must be at least 2 and at least the number of entrants".to_string());
+        }
+        if options.duration.is_some_and(|d| d <= 0.0) {
+            return Err("--duration must be positive".to_string());
+        }
+        Ok(options)
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} expects a number, got {}", flag, value))
+}
+
+fn render(report: &TournamentReport, json: bool) -> String {
+    if json {
+        serde_json::to_string_pretty(report).expect("reports are plain data") + "\n"
+    } else {
+        report.to_csv()
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h") {
+        println!("{}\n\nBot parameters: {}", USAGE, PROFILE_KEYS.join(", "));
+        return ExitCode::SUCCESS;
+    }
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut rules = ModeRules::new(options.mode, &Settings::default());
+    rules.bot_count = options.bots;
+    if let Some(duration) = options.duration {
+        rules.round_duration = duration;
+    }
+    let rounds = options.rounds;
+    let tournament = Tournament::new(rules, options.entrants, options.seed, rounds);
+
+    let started = Instant::now();
+    let report = tournament.run(options.jobs, |done| eprint!("\rRound {}/{}", done, rounds));
+    eprintln!("\rPlayed {} rounds in {:.1}s", rounds, started.elapsed().as_secs_f32());
+
+    let text = render(&report, options.json);
+    match options.output {
+        Some(path) => {
+            if let Err(e) = std::fs::write(&path, text) {
+                eprintln!("Could not write {}: {}", path, e);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", text),
+    }
+    ExitCode::SUCCESS
+}
@@ -2,12 +2,15 @@
 
 use macroquad::prelude::*;
 use ::rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::gameplay::hole::Hole;
+use crate::gameplay::simulation::HoleInput;
+use crate::gameplay::zone::SafeZone;
 use crate::world::objects::WorldObject;
 use crate::world::spatial::SpatialGrid;
 
 /// Bot behavior state
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum BotState {
     /// Looking for objects to consume
     Farming,
@@ -17,55 +20,191 @@ pub enum BotState {
     Fleeing,
     /// Wandering randomly
     Wandering,
+    /// Heading back inside the safe zone
+    ReturningToZone,
+}
+
+/// How well bots play
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum BotDifficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl BotDifficulty {
+    pub const ALL: [BotDifficulty; 4] = [BotDifficulty::Easy, BotDifficulty::Normal, BotDifficulty::Hard, BotDifficulty::Nightmare];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BotDifficulty::Easy => "Easy",
+            BotDifficulty::Normal => "Normal",
+            BotDifficulty::Hard => "Hard",
+            BotDifficulty::Nightmare => "Nightmare",
+        }
+    }
+
+    /// Tier by name, ignoring case (command line flags)
+    pub fn from_name(name: &str) -> Option<BotDifficulty> {
+        Self::ALL.into_iter().find(|d| d.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Behaviour parameters of the tier
+    pub fn profile(&self) -> BotProfile {
+        match self {
+            BotDifficulty::Easy => BotProfile {
+                perception: 0.6,
+                reaction_delay: 0.6,
+                steering_noise: 0.35,
+                dash_usage: 0.0,
+                aggression: 0.3,
+                mistake_rate: 0.25,
+                threat_margin: 1.3,
+                mass_weight: 0.1,
+            },
+            BotDifficulty::Normal => BotProfile {
+                perception: 1.0,
+                reaction_delay: 0.3,
+                steering_noise: 0.12,
+                dash_usage: 0.3,
+                aggression: 0.6,
+                mistake_rate: 0.08,
+                threat_margin: 1.3,
+                mass_weight: 0.1,
+            },
+            BotDifficulty::Hard => BotProfile {
+                perception: 1.3,
+                reaction_delay: 0.15,
+                steering_noise: 0.05,
+                dash_usage: 0.6,
+                aggression: 0.75,
+                mistake_rate: 0.03,
+                threat_margin: 1.3,
+                mass_weight: 0.1,
+            },
+            BotDifficulty::Nightmare => BotProfile {
+                perception: 1.6,
+                reaction_delay: 0.08,
+                steering_noise: 0.0,
+                dash_usage: 0.9,
+                aggression: 0.9,
+                mistake_rate: 0.0,
+                threat_margin: 1.3,
+                mass_weight: 0.1,
+            },
+        }
+    }
+}
+
+/// Bot lineups offered by the match setup: every bot on one tier, or all tiers mixed
+pub const BOT_TIER_PRESETS: [&[BotDifficulty]; 5] = [
+    &[BotDifficulty::Easy],
+    &[BotDifficulty::Normal],
+    &[BotDifficulty::Hard],
+    &[BotDifficulty::Nightmare],
+    &BotDifficulty::ALL,
+];
+
+/// Tunable bot personality
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BotProfile {
+    /// Scale of the threat, prey and object search ranges
+    pub perception: f32,
+    /// Shortest time between two decisions in seconds, a random extra of the same length is added
+    pub reaction_delay: f32,
+    /// Largest aim error in radians, rolled at every decision
+    pub steering_noise: f32,
+    /// Willingness to spend the dash (0-1)
+    pub dash_usage: f32,
+    /// Chance to go after prey when possible (0-1)
+    pub aggression: f32,
+    /// Chance that a decision ignores what the bot sees (0-1)
+    pub mistake_rate: f32,
+    /// Size ratio at which another hole counts as a threat (1.3: 30% larger)
+    pub threat_margin: f32,
+    /// Distance in pixels one unit of object mass is worth when picking what to farm
+    pub mass_weight: f32,
 }
 
 /// Bot controller
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BotController {
+    pub difficulty: BotDifficulty,
+    pub profile: BotProfile,
     pub state: BotState,
     pub target: Option<Vec2>,
     pub state_timer: f32,
     pub wander_angle: f32,
     pub decision_cooldown: f32,
+    /// Aim error applied to the steering until the next decision
+    pub aim_error: f32,
+    /// The current decision is willing to dash (rolled against `dash_usage`)
+    pub dash_committed: bool,
+    /// Farming target sits in a cluster worth rushing to
+    pub rich_target: bool,
 }
 
 impl Default for BotController {
     fn default() -> Self {
+        Self::new(BotDifficulty::default())
+    }
+}
+
+impl BotController {
+    pub fn new(difficulty: BotDifficulty) -> Self {
         Self {
+            difficulty,
+            profile: difficulty.profile(),
             state: BotState::Farming,
             target: None,
             state_timer: 0.0,
             wander_angle: 0.0,
             decision_cooldown: 0.0,
+            aim_error: 0.0,
+            dash_committed: false,
+            rich_target: false,
         }
     }
-}
 
-impl BotController {
-    /// Update bot decision making
+    /// Update bot decision making, returns the bot's input for this step.
+    /// A dash request goes through `Hole::try_dash` like a human's, so the same cooldown applies.
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         &mut self,
         hole: &Hole,
         holes: &[Hole],
         objects: &[WorldObject],
         spatial: &SpatialGrid,
+        zone: Option<&SafeZone>,
         dt: f32,
         rng: &mut impl Rng,
-    ) -> Vec2 {
+    ) -> HoleInput {
         self.state_timer += dt;
         self.decision_cooldown -= dt;
 
         // Make decisions periodically
         if self.decision_cooldown <= 0.0 {
-            self.make_decision(hole, holes, objects, spatial, rng);
-            self.decision_cooldown = 0.3 + rng.gen::<f32>() * 0.3; // 0.3-0.6s between decisions
+            self.make_decision(hole, holes, objects, spatial, zone, rng);
+            let reaction = self.profile.reaction_delay;
+            self.decision_cooldown = reaction + rng.gen::<f32>() * reaction; // 0.3-0.6s on Normal
+            let noise = self.profile.steering_noise;
+            self.aim_error = if noise > 0.0 { rng.gen_range(-noise..noise) } else { 0.0 };
+            self.dash_committed = rng.gen::<f32>() < self.profile.dash_usage;
         }
 
         // Execute current behavior
-        match self.state {
+        let input = match self.state {
             BotState::Farming => self.execute_farming(hole, objects, spatial, rng),
             BotState::Hunting => self.execute_hunting(hole, holes),
             BotState::Fleeing => self.execute_fleeing(hole, holes),
             BotState::Wandering => self.execute_wandering(hole, dt, rng),
+            BotState::ReturningToZone => self.execute_returning(hole, zone),
+        };
+        HoleInput {
+            move_dir: Vec2::from_angle(self.aim_error).rotate(input.move_dir),
+            dash: input.dash && self.dash_committed,
         }
     }
 
@@ -75,8 +214,27 @@ impl BotController {
         holes: &[Hole],
         objects: &[WorldObject],
         spatial: &SpatialGrid,
+        zone: Option<&SafeZone>,
         rng: &mut impl Rng,
     ) {
+        // A mistake: wander off regardless of threats and targets
+        if rng.gen::<f32>() < self.profile.mistake_rate {
+            self.state = BotState::Wandering;
+            self.target = None;
+            return;
+        }
+
+        // Get back inside the zone before it eats us, and stay clear of its edge
+        if let Some(zone) = zone {
+            const ZONE_EDGE_MARGIN: f32 = 1.5; // In own radii
+            let edge = zone.distance_outside(hole.position());
+            if edge > -hole.radius * ZONE_EDGE_MARGIN {
+                self.state = BotState::ReturningToZone;
+                self.target = Some(zone.center);
+                return;
+            }
+        }
+
         // Check for threats (larger holes nearby)
         let threat = self.find_threat(hole, holes);
         if let Some(threat_pos) = threat {
@@ -88,7 +246,7 @@ impl BotController {
         // If large, hunt smaller holes
         if hole.radius > 50.0 {
             if let Some(prey) = self.find_prey(hole, holes) {
-                if rng.gen::<f32>() < 0.6 { // 60% chance to hunt
+                if rng.gen::<f32>() < self.profile.aggression { // 60% chance to hunt on Normal
                     self.state = BotState::Hunting;
                     self.target = Some(prey);
                     return;
@@ -97,9 +255,16 @@ impl BotController {
         }
 
         // Otherwise, farm objects
-        if let Some(target) = self.find_best_object(hole, objects, spatial) {
+        let target = self.find_best_object(hole, objects, spatial)
+            .filter(|t| zone.is_none_or(|z| z.contains(*t)));
+        if let Some(target) = target {
             self.state = BotState::Farming;
             self.target = Some(target);
+            self.rich_target = self.is_rich_cluster(hole, target, objects, spatial);
+        } else if let Some(zone) = zone {
+            // Nothing worth taking inside the zone, drift toward its center instead of out of it
+            self.state = BotState::ReturningToZone;
+            self.target = Some(zone.center);
         } else {
             self.state = BotState::Wandering;
             self.target = None;
@@ -108,7 +273,7 @@ impl BotController {
 
     fn find_threat(&self, hole: &Hole, holes: &[Hole]) -> Option<Vec2> {
         const THREAT_RANGE: f32 = 200.0;
-        const THREAT_MARGIN: f32 = 1.3; // Threat if 30% larger
+        let range = THREAT_RANGE * self.profile.perception;
         
         let mut closest_threat: Option<(f32, Vec2)> = None;
         
@@ -121,10 +286,11 @@ impl BotController {
             let dy = other.y - hole.y;
             let dist = (dx * dx + dy * dy).sqrt();
             
-            if dist < THREAT_RANGE && other.radius > hole.radius * THREAT_MARGIN {
-                if closest_threat.is_none() || dist < closest_threat.unwrap().0 {
-                    closest_threat = Some((dist, vec2(other.x, other.y)));
-                }
+            if dist < range
+                && other.radius > hole.radius * self.profile.threat_margin
+                && (closest_threat.is_none() || dist < closest_threat.unwrap().0)
+            {
+                closest_threat = Some((dist, vec2(other.x, other.y)));
             }
         }
         
@@ -133,6 +299,7 @@ impl BotController {
 
     fn find_prey(&self, hole: &Hole, holes: &[Hole]) -> Option<Vec2> {
         const HUNT_RANGE: f32 = 300.0;
+        let range = HUNT_RANGE * self.profile.perception;
         
         let mut best_prey: Option<(f32, Vec2)> = None;
         
@@ -150,7 +317,7 @@ impl BotController {
             let dy = other.y - hole.y;
             let dist = (dx * dx + dy * dy).sqrt();
             
-            if dist < HUNT_RANGE {
+            if dist < range {
                 // Prefer closer prey
                 let score = dist;
                 if best_prey.is_none() || score < best_prey.unwrap().0 {
@@ -168,7 +335,7 @@ impl BotController {
         objects: &[WorldObject],
         spatial: &SpatialGrid,
     ) -> Option<Vec2> {
-        let nearby = spatial.query_radius(hole.x, hole.y, hole.radius * 4.0);
+        let nearby = spatial.query_radius(hole.x, hole.y, hole.radius * 4.0 * self.profile.perception);
         
         let mut best: Option<(f32, Vec2)> = None;
         
@@ -184,7 +351,7 @@ impl BotController {
             let dist = (dx * dx + dy * dy).sqrt();
             
             // Score: prefer closer, larger objects
-            let score = dist - obj.mass * 0.1;
+            let score = dist - obj.mass * self.profile.mass_weight;
             
             if best.is_none() || score < best.unwrap().0 {
                 best = Some((score, vec2(obj.x, obj.y)));
@@ -194,11 +361,25 @@ impl BotController {
         best.map(|(_, pos)| pos)
     }
 
-    fn execute_farming(&self, hole: &Hole, objects: &[WorldObject], spatial: &SpatialGrid, rng: &mut impl Rng) -> Vec2 {
+    /// Whether the swallowable mass around a target is worth a dash
+    fn is_rich_cluster(&self, hole: &Hole, target: Vec2, objects: &[WorldObject], spatial: &SpatialGrid) -> bool {
+        const CLUSTER_MASS_RATIO: f32 = 0.05; // Cluster mass vs the hole's own area
+        let cluster_mass: f32 = spatial.query_radius(target.x, target.y, hole.radius * 1.5)
+            .into_iter()
+            .map(|idx| &objects[idx])
+            .filter(|obj| !obj.consumed && obj.can_be_swallowed(hole.radius))
+            .map(|obj| obj.mass)
+            .sum();
+        cluster_mass > hole.area * CLUSTER_MASS_RATIO
+    }
+
+    fn execute_farming(&self, hole: &Hole, objects: &[WorldObject], spatial: &SpatialGrid, _rng: &mut impl Rng) -> HoleInput {
         if let Some(target) = self.target {
             let dir = target - vec2(hole.x, hole.y);
             if dir.length() > 1.0 {
-                return dir.normalize();
+                // Rush toward a rich cluster while it is still a few radii away
+                let dash = self.rich_target && dir.length() > hole.radius * 2.0;
+                return HoleInput { move_dir: dir.normalize(), dash };
             }
         }
         
@@ -206,39 +387,63 @@ impl BotController {
         if let Some(new_target) = self.find_best_object(hole, objects, spatial) {
             let dir = new_target - vec2(hole.x, hole.y);
             if dir.length() > 1.0 {
-                return dir.normalize();
+                return HoleInput { move_dir: dir.normalize(), dash: false };
             }
         }
         
-        Vec2::ZERO
+        HoleInput::default()
     }
 
-    fn execute_hunting(&self, hole: &Hole, holes: &[Hole]) -> Vec2 {
+    fn execute_hunting(&self, hole: &Hole, _holes: &[Hole]) -> HoleInput {
+        const STRIKE_RANGE: f32 = 2.5; // In own radii
         if let Some(target) = self.target {
             let dir = target - vec2(hole.x, hole.y);
             if dir.length() > 1.0 {
-                return dir.normalize();
+                // Close the last gap before the prey can react
+                let dash = dir.length() < hole.radius * STRIKE_RANGE;
+                return HoleInput { move_dir: dir.normalize(), dash };
             }
         }
-        Vec2::ZERO
+        HoleInput::default()
     }
 
-    fn execute_fleeing(&self, hole: &Hole, holes: &[Hole]) -> Vec2 {
+    fn execute_fleeing(&self, hole: &Hole, holes: &[Hole]) -> HoleInput {
         if let Some(threat_pos) = self.target {
             // Move away from threat
             let dir = vec2(hole.x, hole.y) - threat_pos;
             if dir.length() > 1.0 {
-                return dir.normalize();
+                let dash = self.threat_in_striking_distance(hole, holes);
+                return HoleInput { move_dir: dir.normalize(), dash };
             }
         }
-        Vec2::ZERO
+        HoleInput::default()
+    }
+
+    /// A hole that can eat us is close enough to reach us with one dash
+    fn threat_in_striking_distance(&self, hole: &Hole, holes: &[Hole]) -> bool {
+        const STRIKE_MARGIN: f32 = 60.0;
+        holes.iter().any(|other| {
+            other.id != hole.id
+                && other.can_consume_hole(hole)
+                && other.position().distance(hole.position()) < other.radius + hole.radius + STRIKE_MARGIN
+        })
+    }
+
+    fn execute_returning(&self, hole: &Hole, zone: Option<&SafeZone>) -> HoleInput {
+        // Follow the zone as it moves, dash when already outside
+        let Some(zone) = zone else { return HoleInput::default() };
+        let dir = zone.center - hole.position();
+        if dir.length() > 1.0 {
+            return HoleInput { move_dir: dir.normalize(), dash: !zone.contains(hole.position()) };
+        }
+        HoleInput::default()
     }
 
-    fn execute_wandering(&mut self, hole: &Hole, dt: f32, rng: &mut impl Rng) -> Vec2 {
+    fn execute_wandering(&mut self, _hole: &Hole, dt: f32, rng: &mut impl Rng) -> HoleInput {
         // Slowly change wander direction
         self.wander_angle += (rng.gen::<f32>() - 0.5) * 2.0 * dt;
         
-        vec2(self.wander_angle.cos(), self.wander_angle.sin())
+        HoleInput { move_dir: vec2(self.wander_angle.cos(), self.wander_angle.sin()), dash: false }
     }
 }
 
@@ -0,0 +1,141 @@
+//! Daily challenge - one city and one set of modifiers per calendar day, the same for everybody
+
+use ::rand::prelude::*;
+use ::rand::SeedableRng;
+use crate::app::settings::Settings;
+use crate::gameplay::bots::BotDifficulty;
+use crate::gameplay::hole::Hole;
+use crate::gameplay::modes::{GameMode, ModeRules};
+use crate::gameplay::simulation::SimRng;
+use crate::time::date::Date;
+use crate::world::gen::WorldSize;
+
+/// Points per object eaten
+pub const OBJECT_POINTS: u32 = 10;
+/// Points per building eaten when only buildings count
+pub const BUILDING_POINTS: u32 = 50;
+/// Points per hole eaten
+pub const ELIMINATION_POINTS: u32 = 100;
+
+/// Twist applied to a daily round
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DailyModifier {
+    DoubleBots,
+    FastClock,
+    BuildingsOnly,
+    LargeCity,
+    HardBots,
+}
+
+impl DailyModifier {
+    pub const ALL: [DailyModifier; 5] = [
+        DailyModifier::DoubleBots,
+        DailyModifier::FastClock,
+        DailyModifier::BuildingsOnly,
+        DailyModifier::LargeCity,
+        DailyModifier::HardBots,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DailyModifier::DoubleBots => "Double Bots",
+            DailyModifier::FastClock => "Fast Clock",
+            DailyModifier::BuildingsOnly => "Buildings Only",
+            DailyModifier::LargeCity => "Large City",
+            DailyModifier::HardBots => "Hard Bots",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            DailyModifier::DoubleBots => "Twice as many bots",
+            DailyModifier::FastClock => "Half the usual round time",
+            DailyModifier::BuildingsOnly => "Only buildings score points",
+            DailyModifier::LargeCity => "Played on a large map",
+            DailyModifier::HardBots => "Every bot is Hard",
+        }
+    }
+}
+
+/// The challenge of one day
+#[derive(Clone, Debug)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub mode: GameMode,
+    pub modifiers: Vec<DailyModifier>,
+}
+
+impl DailyChallenge {
+    /// Challenge for a date; only depends on the date, so every player gets the same one
+    pub fn for_date(date: Date) -> Self {
+        let seed = date_seed(date);
+        let mut rng = SimRng::seed_from_u64(seed);
+        let mode = if rng.gen_bool(0.7) { GameMode::Classic } else { GameMode::Battle };
+        let count = rng.gen_range(1..=2);
+        let mut modifiers: Vec<DailyModifier> = DailyModifier::ALL.choose_multiple(&mut rng, count).copied().collect();
+        modifiers.sort_by_key(|m| DailyModifier::ALL.iter().position(|a| a == m));
+        Self { date, seed, mode, modifiers }
+    }
+
+    pub fn has(&self, modifier: DailyModifier) -> bool {
+        self.modifiers.contains(&modifier)
+    }
+
+    /// Round rules: the defaults (not the player's settings) with the modifiers applied
+    pub fn rules(&self) -> ModeRules {
+        let mut rules = ModeRules::new(self.mode, &Settings::default());
+        for modifier in &self.modifiers {
+            match modifier {
+                DailyModifier::DoubleBots => rules.bot_count *= 2,
+                DailyModifier::FastClock => rules.round_duration *= 0.5,
+                DailyModifier::LargeCity => rules.world_size = WorldSize::Large,
+                DailyModifier::HardBots => rules.bot_tiers = vec![BotDifficulty::Hard],
+                DailyModifier::BuildingsOnly => {} // Scoring only
+            }
+        }
+        rules
+    }
+
+    /// Score of the player's hole at the end of the round
+    pub fn score(&self, player: &Hole) -> u32 {
+        let eaten = if self.has(DailyModifier::BuildingsOnly) {
+            player.buildings_eaten.max(0) as u32 * BUILDING_POINTS
+        } else {
+            player.score.max(0) as u32 * OBJECT_POINTS
+        };
+        eaten + player.eliminations.max(0) as u32 * ELIMINATION_POINTS
+    }
+}
+
+/// Well-mixed seed from a date (splitmix64 of the day number)
+fn date_seed(date: Date) -> u64 {
+    let mut z = (date.to_days() as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_date_always_gives_the_same_challenge() {
+        let date = Date { year: 2026, month: 3, day: 14 };
+        let challenge = DailyChallenge::for_date(date);
+        // Pinned so a change to the generator, which would hand players another city, shows up
+        assert_eq!(challenge.seed, 0x9cb9_f71d_8ba3_6936);
+        assert_eq!(challenge.mode, GameMode::Classic);
+        assert_eq!(challenge.modifiers, [DailyModifier::DoubleBots, DailyModifier::FastClock]);
+
+        let again = DailyChallenge::for_date(date);
+        assert_eq!((again.seed, again.mode, &again.modifiers), (challenge.seed, challenge.mode, &challenge.modifiers));
+        assert_ne!(DailyChallenge::for_date(date.add_days(1)).seed, challenge.seed);
+
+        let defaults = ModeRules::new(GameMode::Classic, &Settings::default());
+        let rules = challenge.rules();
+        assert_eq!(rules.bot_count, defaults.bot_count * 2);
+        assert_eq!(rules.round_duration, defaults.round_duration * 0.5);
+    }
+}
@@ -0,0 +1,19 @@
+//! Gameplay events - what happened during a step, collected for the front end to react to
+
+use crate::gameplay::modes::{GameMode, VictoryResult};
+use crate::world::objects::ObjectType;
+
+/// Something notable that happened during a simulation step
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameEvent {
+    /// A hole captured an object (it starts falling in)
+    ObjectSwallowed { hole_id: u32, object_id: u32, obj_type: ObjectType },
+    /// Same as `ObjectSwallowed`, sent in addition for buildings
+    BuildingSwallowed { hole_id: u32, object_id: u32 },
+    /// A hole was eaten, or lost to the safe zone when `by` is `None`
+    HoleEliminated { hole_id: u32, by: Option<u32> },
+    DashUsed { hole_id: u32 },
+    /// A hole moved on the leaderboard (ranks are 1-indexed)
+    RankChanged { hole_id: u32, old_rank: usize, new_rank: usize },
+    RoundEnded { mode: GameMode, result: VictoryResult },
+}
@@ -1,9 +1,25 @@
 //! Hole entity - the player and bots
 
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::app::storage::ColorDef;
+use crate::gameplay::modes::ModeRules;
+use crate::gameplay::simulation::HoleInput;
+use crate::gameplay::skins::HoleSkin;
+
+/// Color of a human's hole by player slot; the first player keeps the classic blue
+pub fn player_color(slot: usize) -> Color {
+    let colors = [
+        Color::new(0.2, 0.6, 1.0, 1.0),  // Blue
+        Color::new(1.0, 0.45, 0.1, 1.0), // Orange
+        Color::new(0.2, 0.9, 0.4, 1.0),  // Green
+        Color::new(0.95, 0.3, 0.75, 1.0), // Pink
+    ];
+    colors[slot % colors.len()]
+}
 
 /// Hole entity (player or bot)
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Hole {
     pub id: u32,
     pub x: f32,
@@ -11,6 +27,7 @@ pub struct Hole {
     pub radius: f32,
     pub velocity: Vec2,
     pub name: String,
+    #[serde(with = "ColorDef")]
     pub color: Color,
     pub is_player: bool,
     pub is_alive: bool,
@@ -19,6 +36,8 @@ pub struct Hole {
     pub area: f32,           // Current area
     pub score: i32,          // Objects consumed
     pub eliminations: i32,   // Holes consumed
+    pub buildings_eaten: i32, // Buildings among the objects consumed
+    pub peak_radius: f32,    // Largest radius reached this round
     
     // Dash
     pub dash_cooldown: f32,  // Remaining cooldown
@@ -29,32 +48,24 @@ pub struct Hole {
     pub invincible: f32,     // Invincibility after respawn
     
     // Visual
-    pub skin_pattern: u8,    // 0-3 for different patterns
-    pub border_style: u8,    // 0-2 for different borders
+    pub skin_pattern: u8,    // Index into skins::PATTERNS
+    pub border_style: u8,    // Index into skins::BORDERS
     pub pulse_timer: f32,    // For pulsing animation
 }
 
-static mut HOLE_NEXT_ID: u32 = 0;
-
-fn get_hole_id() -> u32 {
-    unsafe {
-        let id = HOLE_NEXT_ID;
-        HOLE_NEXT_ID += 1;
-        id
-    }
-}
-
 impl Hole {
     /// Starting radius
     pub const INITIAL_RADIUS: f32 = 25.0;
     /// Maximum radius before capping growth
     pub const MAX_RADIUS: f32 = 200.0;
+    /// Shrinking below this radius eliminates the hole
+    pub const MIN_RADIUS: f32 = 12.0;
     
-    /// Create a new hole
-    pub fn new(x: f32, y: f32, name: String, color: Color, is_player: bool) -> Self {
+    /// Create a new hole with a session-allocated id
+    pub fn new(id: u32, x: f32, y: f32, name: String, color: Color, is_player: bool) -> Self {
         let area = std::f32::consts::PI * Self::INITIAL_RADIUS * Self::INITIAL_RADIUS;
         Self {
-            id: get_hole_id(),
+            id,
             x, y,
             radius: Self::INITIAL_RADIUS,
             velocity: Vec2::ZERO,
@@ -65,6 +76,8 @@ impl Hole {
             area,
             score: 0,
             eliminations: 0,
+            buildings_eaten: 0,
+            peak_radius: Self::INITIAL_RADIUS,
             dash_cooldown: 0.0,
             dash_active: 0.0,
             respawn_timer: 0.0,
@@ -76,17 +89,29 @@ impl Hole {
     }
 
     /// Create player hole
-    pub fn new_player(x: f32, y: f32, name: String) -> Self {
-        Self::new(x, y, name, Color::new(0.2, 0.6, 1.0, 1.0), true)
+    pub fn new_player(id: u32, x: f32, y: f32, name: String) -> Self {
+        Self::new(id, x, y, name, player_color(0), true)
     }
 
     /// Create bot hole
-    pub fn new_bot(x: f32, y: f32, name: String, color: Color) -> Self {
-        Self::new(x, y, name, color, false)
+    pub fn new_bot(id: u32, x: f32, y: f32, name: String, color: Color) -> Self {
+        Self::new(id, x, y, name, color, false)
+    }
+
+    /// Skin the hole is drawn with
+    pub fn skin(&self) -> HoleSkin {
+        HoleSkin { pattern: self.skin_pattern, border: self.border_style }
+    }
+
+    pub fn set_skin(&mut self, skin: HoleSkin) {
+        self.skin_pattern = skin.pattern;
+        self.border_style = skin.border;
     }
 
     /// Update hole state
-    pub fn update(&mut self, dt: f32, world_width: f32, world_height: f32, move_speed: f32) {
+    pub fn update(&mut self, dt: f32, world_width: f32, world_height: f32, rules: &ModeRules) {
+        self.peak_radius = self.peak_radius.max(self.radius);
+
         // Update timers
         if self.dash_cooldown > 0.0 {
             self.dash_cooldown -= dt;
@@ -109,9 +134,8 @@ impl Hole {
         self.pulse_timer += dt;
 
         // Apply velocity with speed adjustment for size
-        let size_penalty = (self.radius / 50.0).min(1.5);
-        let effective_speed = move_speed / (1.0 + size_penalty * 0.3);
-        let dash_mult = if self.dash_active > 0.0 { 2.5 } else { 1.0 };
+        let effective_speed = self.effective_speed(rules.move_speed);
+        let dash_mult = if self.dash_active > 0.0 { rules.dash_speed_mult } else { 1.0 };
         
         self.x += self.velocity.x * effective_speed * dash_mult * dt;
         self.y += self.velocity.y * effective_speed * dash_mult * dt;
@@ -121,15 +145,31 @@ impl Hole {
         self.y = self.y.clamp(self.radius, world_height - self.radius);
     }
 
-    /// Set movement direction (normalized)
+    /// Movement speed for a base speed; larger holes move slightly slower
+    pub fn effective_speed(&self, move_speed: f32) -> f32 {
+        let size_penalty = (self.radius / 50.0).min(1.5);
+        move_speed / (1.0 + size_penalty * 0.3)
+    }
+
+    /// Steer with a throttle: the direction of `vel`, its length (capped at 1) the fraction of full speed
     pub fn set_velocity(&mut self, vel: Vec2) {
-        if vel.length() > 0.01 {
+        let len = vel.length();
+        if len > 0.99 {
+            // Full deflection, also absorbs float error in unit vectors from keys and bots
             self.velocity = vel.normalize();
+        } else if len > 0.01 {
+            self.velocity = vel;
         } else {
             self.velocity = Vec2::ZERO;
         }
     }
 
+    /// Steer and, if requested, dash under the round's cooldown rules; returns true if a dash started
+    pub fn apply_input(&mut self, input: &HoleInput, rules: &ModeRules) -> bool {
+        self.set_velocity(input.move_dir);
+        input.dash && self.try_dash(rules.dash_cooldown, rules.dash_duration)
+    }
+
     /// Attempt to dash
     pub fn try_dash(&mut self, dash_cooldown: f32, dash_duration: f32) -> bool {
         if self.dash_cooldown <= 0.0 && self.velocity.length() > 0.01 {
@@ -148,6 +188,13 @@ impl Hole {
         self.score += 1;
     }
 
+    /// Shrink by losing area, returns true once the hole is too small to survive
+    pub fn lose_area(&mut self, amount: f32) -> bool {
+        self.area = (self.area - amount).max(0.0);
+        self.radius = (self.area / std::f32::consts::PI).sqrt();
+        self.radius < Self::MIN_RADIUS
+    }
+
     /// Consume another hole
     pub fn consume_hole(&mut self, other: &Hole) {
         self.area += other.area * 0.5; // Get half the area
@@ -3,3 +3,11 @@ pub mod swallow;
 pub mod modes;
 pub mod bots;
 pub mod scoring;
+pub mod simulation;
+pub mod replay;
+pub mod savegame;
+pub mod zone;
+pub mod skins;
+pub mod events;
+pub mod daily;
+pub mod tournament;
@@ -1,9 +1,16 @@
 //! Game modes - Classic, Battle, Solo
 
+use serde::{Deserialize, Serialize};
+use crate::app::settings::Settings;
+use crate::gameplay::bots::BotDifficulty;
+use crate::gameplay::swallow::GROWTH_MULTIPLIER;
+use crate::world::gen::WorldSize;
+
 /// Available game modes
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum GameMode {
     /// 2 minute timed, biggest hole wins
+    #[default]
     Classic,
     /// Last hole standing
     Battle,
@@ -12,6 +19,8 @@ pub enum GameMode {
 }
 
 impl GameMode {
+    pub const ALL: [GameMode; 3] = [GameMode::Classic, GameMode::Battle, GameMode::Solo];
+
     pub fn name(&self) -> &'static str {
         match self {
             GameMode::Classic => "Classic",
@@ -20,6 +29,11 @@ impl GameMode {
         }
     }
 
+    /// Mode by name, ignoring case (command line flags)
+    pub fn from_name(name: &str) -> Option<GameMode> {
+        Self::ALL.into_iter().find(|m| m.name().eq_ignore_ascii_case(name))
+    }
+
     pub fn description(&self) -> &'static str {
         match self {
             GameMode::Classic => "Be the biggest hole when time runs out!",
@@ -52,64 +66,94 @@ impl GameMode {
         }
     }
 
-    pub fn round_duration(&self) -> f32 {
+    /// Round length for the configured base duration
+    pub fn round_duration(&self, base: f32) -> f32 {
         match self {
-            GameMode::Classic => 120.0,  // 2 minutes
-            GameMode::Battle => 300.0,   // 5 minutes max
-            GameMode::Solo => 120.0,     // 2 minutes
+            GameMode::Classic => base,
+            GameMode::Battle => base * 2.5, // Time limit only, usually ends by elimination
+            GameMode::Solo => base,
         }
     }
 }
 
-impl Default for GameMode {
-    fn default() -> Self {
-        Self::Classic
-    }
-}
-
-/// Mode-specific rules
+/// Rules of a round: the mode's fixed rules combined with the player's settings and any
+/// match setup overrides. Captured when the round is created so a running round is not
+/// affected by later edits.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModeRules {
     pub mode: GameMode,
     pub bot_count: usize,
+    /// Skill tier of each bot, repeated when there are more bots than entries
+    pub bot_tiers: Vec<BotDifficulty>,
+    pub world_size: WorldSize,
+    /// Area gained per unit of swallowed mass
+    pub growth_multiplier: f32,
+    /// Round length in seconds
+    pub round_duration: f32,
     pub respawn_time: f32,
     pub safe_zone_shrink: bool,
+    /// Base hole speed before the size penalty
+    pub move_speed: f32,
+    pub dash_cooldown: f32,
+    pub dash_duration: f32,
+    pub dash_speed_mult: f32,
 }
 
 impl ModeRules {
-    pub fn new(mode: GameMode) -> Self {
-        match mode {
-            GameMode::Classic => Self {
-                mode,
-                bot_count: 5,
-                respawn_time: 3.0,
-                safe_zone_shrink: false,
-            },
-            GameMode::Battle => Self {
-                mode,
-                bot_count: 5,
-                respawn_time: 0.0, // No respawn
-                safe_zone_shrink: true,
-            },
-            GameMode::Solo => Self {
-                mode,
-                bot_count: 0,
-                respawn_time: 0.0,
-                safe_zone_shrink: false,
-            },
+    pub fn new(mode: GameMode, settings: &Settings) -> Self {
+        let (respawn_time, safe_zone_shrink) = match mode {
+            GameMode::Classic => (3.0, false),
+            GameMode::Battle => (0.0, true), // No respawn
+            GameMode::Solo => (0.0, false),
+        };
+        Self {
+            mode,
+            bot_count: if mode.has_bots() { settings.bot_count } else { 0 },
+            bot_tiers: vec![BotDifficulty::default()],
+            world_size: WorldSize::default(),
+            growth_multiplier: GROWTH_MULTIPLIER,
+            round_duration: mode.round_duration(settings.round_duration),
+            respawn_time,
+            safe_zone_shrink,
+            move_speed: settings.move_speed,
+            dash_cooldown: settings.dash_cooldown,
+            dash_duration: settings.dash_duration,
+            dash_speed_mult: settings.dash_speed_mult,
         }
     }
 }
 
+impl ModeRules {
+    /// Skill tier of bot number `index` (0-based, not counting humans)
+    pub fn bot_tier(&self, index: usize) -> BotDifficulty {
+        if self.bot_tiers.is_empty() {
+            return BotDifficulty::default();
+        }
+        self.bot_tiers[index % self.bot_tiers.len()]
+    }
+}
+
+/// How the player last left the round
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Elimination {
+    /// Hole that ate the player, `None` when the safe zone got them
+    pub killer_name: Option<String>,
+    /// Round time elapsed at that moment, in seconds
+    pub time: f32,
+}
+
 /// Victory condition result
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum VictoryResult {
+    /// Round still running
+    #[default]
     None,
     /// Timer ended, show rankings
     TimeUp { winner_name: String, player_rank: usize },
     /// Player won (last standing)
     PlayerWon,
     /// Player was eliminated
-    PlayerEliminated { killer_name: String },
+    PlayerEliminated { killer_name: Option<String>, time: f32 },
     /// Player consumed all objects
     CityConsumed { percentage: f32 },
 }
@@ -137,7 +181,8 @@ pub fn check_victory(
         GameMode::Battle => {
             if !player_alive {
                 VictoryResult::PlayerEliminated {
-                    killer_name: String::new(),
+                    killer_name: None, // Will be filled by caller
+                    time: 0.0,
                 }
             } else if alive_hole_count == 1 && is_player_winner {
                 VictoryResult::PlayerWon
@@ -0,0 +1,216 @@
+//! Input recording and replay files
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::app::storage::{self, StorageError};
+use crate::gameplay::modes::{GameMode, ModeRules};
+use crate::gameplay::simulation::{HoleInput, PlayerSlot, Simulation};
+
+/// Magic bytes at the start of every replay file
+pub const REPLAY_MAGIC: [u8; 4] = *b"HIOR";
+/// Current replay format version, bump when the layout or the simulation RNG changes
+pub const REPLAY_VERSION: u32 = 9;
+/// File extension for saved replays
+pub const REPLAY_EXTENSION: &str = "hioreplay";
+
+/// Everything needed to reproduce a round: its setup plus every human's input on every tick
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: ModeRules,
+    /// Humans in the round, in hole order
+    pub players: Vec<PlayerSlot>,
+    /// Human input, one entry per player per tick (tick-major)
+    pub inputs: Vec<HoleInput>,
+}
+
+impl Replay {
+    /// Start an empty recording for a round
+    pub fn new(seed: u64, rules: &ModeRules, players: &[PlayerSlot]) -> Self {
+        Self {
+            seed,
+            rules: rules.clone(),
+            players: players.to_vec(),
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Append the inputs used for the next tick, one per player
+    pub fn record(&mut self, inputs: &[HoleInput]) {
+        debug_assert_eq!(inputs.len(), self.players.len());
+        self.inputs.extend_from_slice(inputs);
+    }
+
+    /// Inputs recorded for a tick (idle past the end of the recording)
+    pub fn inputs_at(&self, tick: u64) -> Vec<HoleInput> {
+        let n = self.players.len();
+        let start = tick as usize * n;
+        match self.inputs.get(start..start + n) {
+            Some(inputs) => inputs.to_vec(),
+            None => vec![HoleInput::default(); n],
+        }
+    }
+
+    /// Number of recorded ticks
+    pub fn len(&self) -> usize {
+        self.inputs.len() / self.players.len().max(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Build a fresh simulation with the recorded setup
+    pub fn simulation(&self) -> Simulation {
+        Simulation::with_players(self.rules.clone(), &self.players, self.seed)
+    }
+
+    /// Encode to the versioned binary format
+    pub fn to_bytes(&self) -> Vec<u8> {
+        storage::encode_versioned(REPLAY_MAGIC, REPLAY_VERSION, self)
+    }
+
+    /// Decode from the versioned binary format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, StorageError> {
+        storage::decode_versioned(REPLAY_MAGIC, REPLAY_VERSION, bytes)
+    }
+
+    /// Save to a file
+    pub fn save(&self, path: &Path) -> Result<(), StorageError> {
+        storage::write_file(path, &self.to_bytes())
+    }
+
+    /// Load from a file
+    pub fn load(path: &Path) -> Result<Self, StorageError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+/// Directory where finished rounds are saved
+pub fn replay_dir() -> PathBuf {
+    storage::data_dir().join("replays")
+}
+
+/// File name for a replay finished at the given unix time
+pub fn replay_file_name(unix_time: u64, mode: GameMode, seed: u64) -> String {
+    format!("{}-{}-{:016x}.{}", unix_time, mode.name().to_lowercase(), seed, REPLAY_EXTENSION)
+}
+
+/// Ticks between keyframes kept by the replay player (5 seconds at 60 Hz)
+pub const KEYFRAME_INTERVAL: u64 = 300;
+
+/// Plays a replay back with random access: periodic keyframes of the whole
+/// simulation let it jump to any tick without re-running the round from the start.
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub sim: Simulation,
+    /// `keyframes[i]` is the simulation state at tick `i * KEYFRAME_INTERVAL`
+    keyframes: Vec<Simulation>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let mut sim = replay.simulation();
+        sim.start();
+        Self {
+            keyframes: vec![sim.clone()],
+            replay,
+            sim,
+        }
+    }
+
+    /// Current tick
+    pub fn tick(&self) -> u64 {
+        self.sim.tick
+    }
+
+    /// Length of the recording in ticks
+    pub fn total_ticks(&self) -> u64 {
+        self.replay.len() as u64
+    }
+
+    /// Has playback reached the end of the round?
+    pub fn is_finished(&self) -> bool {
+        self.sim.game_over || self.tick() >= self.total_ticks()
+    }
+
+    /// Advance one tick with the recorded input
+    pub fn step(&mut self, dt: f32) {
+        if self.is_finished() {
+            return;
+        }
+        let inputs = self.replay.inputs_at(self.sim.tick);
+        self.sim.step(&inputs, dt);
+
+        let tick = self.sim.tick;
+        if tick.is_multiple_of(KEYFRAME_INTERVAL) && (tick / KEYFRAME_INTERVAL) as usize == self.keyframes.len() {
+            let mut keyframe = self.sim.clone();
+            keyframe.effects.clear();
+            keyframe.events.clear();
+            self.keyframes.push(keyframe);
+        }
+    }
+
+    /// Jump to a tick, restoring the nearest earlier keyframe and simulating forward from it
+    pub fn seek(&mut self, target: u64, dt: f32) {
+        let target = target.min(self.total_ticks());
+        let idx = ((target / KEYFRAME_INTERVAL) as usize).min(self.keyframes.len() - 1);
+        let keyframe_tick = idx as u64 * KEYFRAME_INTERVAL;
+
+        // Rewinding, or a keyframe is closer than the current position
+        if target < self.sim.tick || keyframe_tick > self.sim.tick {
+            self.sim = self.keyframes[idx].clone();
+        }
+        while self.sim.tick < target && !self.is_finished() {
+            self.step(dt);
+        }
+        self.sim.effects.clear();
+        self.sim.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::skins::HoleSkin;
+    use crate::time::tick::TICK_DT;
+    use macroquad::math::Vec2;
+
+    /// Serialized holes and objects, to compare two simulations bit for bit
+    fn state(sim: &Simulation) -> (Vec<u8>, Vec<u8>) {
+        (bincode::serialize(&sim.holes).unwrap(), bincode::serialize(&sim.world.objects).unwrap())
+    }
+
+    #[test]
+    fn playback_and_seek_reproduce_the_recorded_round() {
+        const SEEK_TICK: u64 = KEYFRAME_INTERVAL + 150;
+        let rules = ModeRules::new(GameMode::Classic, &Settings::default());
+        let players = [PlayerSlot::new("Ana", HoleSkin::default())];
+        let mut replay = Replay::new(7, &rules, &players);
+        let mut sim = replay.simulation();
+        sim.start();
+        let mut at_seek = None;
+        for tick in 0..2 * KEYFRAME_INTERVAL + 100 {
+            let inputs = [HoleInput { move_dir: Vec2::from_angle(tick as f32 * 0.01), dash: tick % 120 == 5 }];
+            replay.record(&inputs);
+            sim.step(&inputs, TICK_DT);
+            if sim.tick == SEEK_TICK {
+                at_seek = Some(state(&sim));
+            }
+        }
+
+        let replay = Replay::from_bytes(&replay.to_bytes()).expect("replay decodes");
+        let mut player = ReplayPlayer::new(replay);
+        while !player.is_finished() {
+            player.step(TICK_DT);
+        }
+        assert_eq!(player.tick(), sim.tick);
+        assert_eq!(state(&player.sim), state(&sim));
+
+        // Back to a keyframe and forward from it
+        player.seek(SEEK_TICK, TICK_DT);
+        assert_eq!(player.tick(), SEEK_TICK);
+        assert_eq!(Some(state(&player.sim)), at_seek);
+    }
+}
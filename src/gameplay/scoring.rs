@@ -1,9 +1,10 @@
 //! Scoring, leaderboard, and ranking
 
+use serde::{Deserialize, Serialize};
 use crate::gameplay::hole::Hole;
 
 /// Leaderboard entry
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct LeaderboardEntry {
     pub id: u32,
     pub name: String,
@@ -15,6 +16,7 @@ pub struct LeaderboardEntry {
 }
 
 /// Leaderboard system
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Leaderboard {
     entries: Vec<LeaderboardEntry>,
     previous_ranks: std::collections::HashMap<u32, usize>,
@@ -83,6 +85,11 @@ impl Leaderboard {
         self.entries.iter().position(|e| e.is_player).map(|i| i + 1)
     }
 
+    /// Get a hole's rank (1-indexed) by id
+    pub fn get_rank(&self, id: u32) -> Option<usize> {
+        self.entries.iter().position(|e| e.id == id).map(|i| i + 1)
+    }
+
     /// Get player's entry
     pub fn get_player_entry(&self) -> Option<&LeaderboardEntry> {
         self.entries.iter().find(|e| e.is_player)
@@ -99,13 +106,27 @@ impl Leaderboard {
     }
 }
 
+/// Where a human finished in a round shared by several humans
+#[derive(Clone, Debug)]
+pub struct Standing {
+    /// Player slot, also the hole index
+    pub slot: usize,
+    pub name: String,
+    /// Leaderboard rank, holes that are out rank last
+    pub rank: usize,
+    pub size: f32,
+    pub eliminations: i32,
+    /// Objects eaten
+    pub score: i32,
+}
+
 /// Calculate XP from a game
 pub fn calculate_xp(
     time_alive: f32,
     objects_consumed: i32,
     eliminations: i32,
     final_rank: usize,
-    total_players: usize,
+    _total_players: usize,
 ) -> i32 {
     let time_xp = (time_alive / 10.0) as i32;
     let object_xp = objects_consumed * 2;
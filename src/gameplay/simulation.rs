@@ -0,0 +1,398 @@
+//! Headless simulation core - advances a round from plain inputs, no window needed
+
+use macroquad::prelude::*;
+use ::rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use crate::world::gen::World;
+use crate::world::ids::IdAllocator;
+use crate::world::spatial::SpatialGrid;
+use crate::gameplay::events::GameEvent;
+use crate::gameplay::hole::{player_color, Hole};
+use crate::gameplay::modes::{check_victory, Elimination, ModeRules, VictoryResult};
+use crate::gameplay::bots::{BotController, BOT_NAMES, get_bot_color};
+use crate::gameplay::scoring::{Leaderboard, Standing};
+use crate::gameplay::skins::HoleSkin;
+use crate::gameplay::swallow;
+use crate::gameplay::zone::SafeZone;
+use crate::render::vfx::VfxType;
+use crate::time::clock::GameClock;
+
+/// Session random stream; every gameplay roll (spawns, bots) draws from it.
+/// ChaCha is portable and its state can be saved with the session.
+pub type SimRng = ChaCha8Rng;
+
+/// Mixed into the seed for cosmetic rolls, so they never shift the gameplay stream
+const COSMETIC_SEED_SALT: u64 = 0x5c1f_6b3a_9e07_d24d;
+
+/// Input for one human-controlled hole during a single step
+#[derive(Clone, Copy, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HoleInput {
+    /// Desired movement: direction, and length up to 1 as the fraction of full speed
+    pub move_dir: Vec2,
+    /// Dash requested this step
+    pub dash: bool,
+}
+
+/// A human player joining a round
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSlot {
+    pub name: String,
+    pub skin: HoleSkin,
+}
+
+impl PlayerSlot {
+    pub fn new(name: &str, skin: HoleSkin) -> Self {
+        Self { name: name.to_string(), skin }
+    }
+}
+
+/// Complete gameplay state of a round
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Simulation {
+    pub world: World,
+    pub spatial: SpatialGrid,
+    pub holes: Vec<Hole>,
+    pub bot_controllers: Vec<BotController>,
+    /// The local player's hole: the first human
+    pub player_idx: usize,
+    /// Humans are holes `0..human_count`, bots follow
+    pub human_count: usize,
+    pub clock: GameClock,
+    pub leaderboard: Leaderboard,
+    pub mode_rules: ModeRules,
+    /// Shrinking safe zone, only in modes with `safe_zone_shrink`
+    pub zone: Option<SafeZone>,
+    pub game_over: bool,
+    /// Outcome of the round, `VictoryResult::None` while it is running
+    pub result: VictoryResult,
+    /// Latest time each human was eaten or lost to the zone, by human index
+    pub human_eliminations: Vec<Option<Elimination>>,
+    /// Number of ticks simulated so far
+    pub tick: u64,
+    /// Seed the round was generated from
+    pub seed: u64,
+    /// Visual effects requested by the last steps, drained by the front end
+    #[serde(skip)]
+    pub effects: Vec<VfxType>,
+    /// Gameplay events from the last steps, drained by the front end
+    #[serde(skip)]
+    pub events: Vec<GameEvent>,
+    rng: SimRng,
+}
+
+impl Simulation {
+    /// Create a new round; the player is always hole 0, bots follow
+    pub fn new(mode_rules: ModeRules, player_name: &str, player_skin: HoleSkin, seed: u64) -> Self {
+        Self::with_players(mode_rules, &[PlayerSlot::new(player_name, player_skin)], seed)
+    }
+
+    /// Create a round for several humans sharing the machine; they are holes `0..players.len()`
+    pub fn with_players(mode_rules: ModeRules, players: &[PlayerSlot], seed: u64) -> Self {
+        let mut rng = SimRng::seed_from_u64(seed);
+        let mut cosmetic_rng = SimRng::seed_from_u64(seed ^ COSMETIC_SEED_SALT);
+        let mut hole_ids = IdAllocator::new();
+        let world = World::generate(seed, mode_rules.world_size);
+
+        // Create the humans
+        let mut holes = Vec::new();
+        let mut bot_controllers = Vec::new();
+        for (slot, p) in players.iter().enumerate() {
+            let pos = world.get_spawn_position(&mut rng);
+            let mut human = Hole::new_player(hole_ids.next_id(), pos.x, pos.y, p.name.clone());
+            human.color = player_color(slot);
+            human.set_skin(p.skin);
+            holes.push(human);
+            bot_controllers.push(BotController::default()); // Placeholder for a human
+        }
+
+        // Create bots
+        for i in 0..mode_rules.bot_count {
+            let pos = world.get_spawn_position(&mut rng);
+            let name = BOT_NAMES[i % BOT_NAMES.len()].to_string();
+            let color = get_bot_color(i);
+            let mut bot = Hole::new_bot(hole_ids.next_id(), pos.x, pos.y, name, color);
+            bot.set_skin(HoleSkin::random(&mut cosmetic_rng));
+            holes.push(bot);
+            bot_controllers.push(BotController::new(mode_rules.bot_tier(i)));
+        }
+
+        let mut spatial = SpatialGrid::new();
+        spatial.build(&world.objects);
+
+        let clock = GameClock::new(mode_rules.round_duration);
+        let zone = mode_rules.safe_zone_shrink
+            .then(|| SafeZone::new(world.width, world.height, mode_rules.round_duration, &mut rng));
+
+        Self {
+            world,
+            spatial,
+            holes,
+            bot_controllers,
+            player_idx: 0,
+            human_count: players.len(),
+            clock,
+            leaderboard: Leaderboard::new(),
+            mode_rules,
+            zone,
+            game_over: false,
+            result: VictoryResult::None,
+            human_eliminations: vec![None; players.len()],
+            tick: 0,
+            seed,
+            effects: Vec::new(),
+            events: Vec::new(),
+            rng,
+        }
+    }
+
+    /// Start the round clock
+    pub fn start(&mut self) {
+        self.clock.start();
+    }
+
+    /// Advance the round by `dt` seconds.
+    ///
+    /// `inputs[i]` drives human hole `i`; bot holes are driven by their controllers.
+    pub fn step(&mut self, inputs: &[HoleInput], dt: f32) {
+        if self.game_over { return; }
+        self.tick += 1;
+
+        // Update clock
+        self.clock.update(dt);
+        if let Some(zone) = &mut self.zone {
+            zone.update(dt, &mut self.rng);
+        }
+
+        // Human input
+        for (hole, input) in self.holes.iter_mut().zip(inputs) {
+            if hole.is_player && hole.is_alive && hole.apply_input(input, &self.mode_rules) {
+                self.events.push(GameEvent::DashUsed { hole_id: hole.id });
+            }
+        }
+
+        // Update bot AI, bots steer and dash through the same input as humans
+        for i in 0..self.holes.len() {
+            let hole = self.holes[i].clone();
+            if !hole.is_player && hole.is_alive {
+                let input = self.bot_controllers[i].update(&hole, &self.holes, &self.world.objects, &self.spatial, self.zone.as_ref(), dt, &mut self.rng);
+                if self.holes[i].apply_input(&input, &self.mode_rules) {
+                    self.events.push(GameEvent::DashUsed { hole_id: hole.id });
+                }
+            }
+        }
+
+        // Update all holes
+        for hole in &mut self.holes {
+            hole.update(dt, self.world.width, self.world.height, &self.mode_rules);
+        }
+
+        // Holes outside the safe zone lose area until they are too small to survive
+        if let Some(zone) = &self.zone {
+            for (i, hole) in self.holes.iter_mut().enumerate() {
+                if hole.is_alive && !zone.contains(hole.position()) && hole.lose_area(SafeZone::area_loss(hole.area, dt)) {
+                    self.effects.push(VfxType::SwallowParticles { x: hole.x, y: hole.y, color: hole.color, count: 30 });
+                    hole.die(self.mode_rules.respawn_time);
+                    self.events.push(GameEvent::HoleEliminated { hole_id: hole.id, by: None });
+                    if let Some(slot) = self.human_eliminations.get_mut(i) {
+                        *slot = Some(Elimination { killer_name: None, time: self.clock.elapsed });
+                    }
+                }
+            }
+        }
+
+        // Rebuild spatial grid
+        self.spatial.build(&self.world.objects);
+
+        // Process swallowing for each hole
+        for hole in &mut self.holes {
+            if hole.is_alive {
+                swallow::process_swallow(hole, &mut self.world.objects, &self.spatial, &mut self.effects, &mut self.events);
+            }
+        }
+
+        // Update falling objects
+        swallow::update_falling_objects(&mut self.holes, &mut self.world.objects, dt, self.mode_rules.growth_multiplier);
+
+        // Hole vs hole combat
+        let eliminations = swallow::process_hole_combat(&mut self.holes, &mut self.effects, &mut self.events, self.mode_rules.mode.allows_respawn(), self.mode_rules.respawn_time);
+        for (winner, loser) in eliminations {
+            if let Some(slot) = self.human_eliminations.get_mut(loser) {
+                *slot = Some(Elimination { killer_name: Some(self.holes[winner].name.clone()), time: self.clock.elapsed });
+            }
+        }
+
+        // Respawn dead holes at new positions
+        for hole in &mut self.holes {
+            if !hole.is_alive && hole.respawn_timer <= 0.0 && self.mode_rules.mode.allows_respawn() {
+                let pos = self.world.get_spawn_position(&mut self.rng);
+                hole.respawn(pos.x, pos.y);
+            }
+        }
+
+        // Update leaderboard
+        let old_ranks: Vec<Option<usize>> = self.holes.iter().map(|h| self.leaderboard.get_rank(h.id)).collect();
+        self.leaderboard.update(&self.holes);
+        for (hole, old_rank) in self.holes.iter().zip(old_ranks) {
+            if let (Some(old_rank), Some(new_rank)) = (old_rank, self.leaderboard.get_rank(hole.id)) {
+                if old_rank != new_rank {
+                    self.events.push(GameEvent::RankChanged { hole_id: hole.id, old_rank, new_rank });
+                }
+            }
+        }
+
+        // Check victory conditions
+        self.result = self.check_victory();
+        self.game_over = self.result != VictoryResult::None;
+        if self.game_over {
+            self.events.push(GameEvent::RoundEnded { mode: self.mode_rules.mode, result: self.result.clone() });
+        }
+    }
+
+    /// Evaluate the mode's victory conditions and fill in the names behind the outcome
+    fn check_victory(&self) -> VictoryResult {
+        let alive_count = self.holes.iter().filter(|h| h.is_alive).count();
+        // With several humans the round goes on while any of them is alive, and any of them can win.
+        // A round of bots only has nobody to lose and runs until its clock.
+        let humans_alive = self.human_count == 0 || self.humans().iter().any(|h| h.is_alive);
+        let is_player_winner = self.leaderboard.get_winner().is_some_and(|w| w.is_player);
+        let result = check_victory(
+            &self.mode_rules,
+            self.clock.remaining,
+            humans_alive,
+            alive_count,
+            self.world.get_consumption_percentage(),
+            is_player_winner,
+        );
+
+        match result {
+            VictoryResult::TimeUp { .. } => VictoryResult::TimeUp {
+                winner_name: self.leaderboard.get_winner().map(|w| w.name.clone()).unwrap_or_default(),
+                player_rank: self.leaderboard.get_player_rank().unwrap_or(self.holes.len()),
+            },
+            VictoryResult::PlayerEliminated { .. } => {
+                // The human who went out last ends the round
+                let last = self.human_eliminations.iter().flatten().max_by(|a, b| a.time.total_cmp(&b.time));
+                let (killer_name, time) = match last {
+                    Some(e) => (e.killer_name.clone(), e.time),
+                    None => (None, self.clock.elapsed),
+                };
+                VictoryResult::PlayerEliminated { killer_name, time }
+            }
+            other => other,
+        }
+    }
+
+    /// The local player's hole
+    pub fn player(&self) -> &Hole {
+        &self.holes[self.player_idx]
+    }
+
+    /// Every human's hole, in player order
+    pub fn humans(&self) -> &[Hole] {
+        &self.holes[..self.human_count]
+    }
+
+    /// Latest elimination of the local player
+    pub fn player_elimination(&self) -> Option<&Elimination> {
+        self.human_eliminations.get(self.player_idx).and_then(|e| e.as_ref())
+    }
+
+    /// Leaderboard rank (1-indexed) of a hole; holes that are out rank last
+    pub fn rank_of(&self, idx: usize) -> usize {
+        self.leaderboard.get_rank(self.holes[idx].id).unwrap_or(self.holes.len())
+    }
+
+    /// Every human, best first; humans who are out are ordered by how long they lasted
+    pub fn standings(&self) -> Vec<Standing> {
+        let out_time = |slot: usize| self.human_eliminations[slot].as_ref().map_or(f32::MAX, |e| e.time);
+        let mut slots: Vec<usize> = (0..self.human_count).collect();
+        slots.sort_by(|&a, &b| {
+            self.rank_of(a).cmp(&self.rank_of(b))
+                .then(out_time(b).total_cmp(&out_time(a)))
+                .then(self.holes[b].peak_radius.total_cmp(&self.holes[a].peak_radius))
+        });
+        slots.into_iter().map(|slot| {
+            let hole = &self.holes[slot];
+            Standing {
+                slot,
+                name: hole.name.clone(),
+                rank: self.rank_of(slot),
+                size: hole.radius,
+                eliminations: hole.eliminations,
+                score: hole.score,
+            }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::modes::GameMode;
+    use crate::time::tick::TICK_DT;
+
+    /// Run a round with scripted input and return the serialized holes and objects
+    fn scripted_run(seed: u64) -> (Vec<u8>, Vec<u8>) {
+        let rules = ModeRules::new(GameMode::Classic, &Settings::default());
+        let players = [PlayerSlot::new("Ana", HoleSkin::default()), PlayerSlot::new("Bia", HoleSkin::default())];
+        let mut sim = Simulation::with_players(rules, &players, seed);
+        sim.start();
+        for tick in 0..400 {
+            let angle = tick as f32 * 0.02;
+            let inputs = [
+                HoleInput { move_dir: Vec2::from_angle(angle), dash: tick % 90 == 0 },
+                HoleInput { move_dir: Vec2::from_angle(-angle) * 0.5, dash: tick % 130 == 7 },
+            ];
+            sim.step(&inputs, TICK_DT);
+        }
+        (
+            bincode::serialize(&sim.holes).expect("bincode serialization"),
+            bincode::serialize(&sim.world.objects).expect("bincode serialization"),
+        )
+    }
+
+    #[test]
+    fn same_seed_and_inputs_give_identical_state() {
+        let first = scripted_run(42);
+        assert_eq!(first, scripted_run(42));
+        let other = scripted_run(43);
+        assert_ne!(first.0, other.0);
+        assert_ne!(first.1, other.1);
+    }
+
+    #[test]
+    fn a_player_eaten_by_a_bot_names_it_as_the_killer() {
+        let mut rules = ModeRules::new(GameMode::Battle, &Settings::default());
+        rules.bot_count = 2;
+        let mut sim = Simulation::with_players(rules, &[PlayerSlot::new("Ana", HoleSkin::default())], 4);
+        // Let the first bot grow well past the player and put it right on top of them
+        let (x, y) = (sim.player().x, sim.player().y);
+        sim.holes[0].invincible = 0.0;
+        let bot = &mut sim.holes[1];
+        bot.x = x;
+        bot.y = y;
+        bot.area *= 4.0;
+        bot.radius = (bot.area / std::f32::consts::PI).sqrt();
+        let killer = bot.name.clone();
+        sim.start();
+
+        let idle = [HoleInput::default()];
+        let mut eaten_at = None;
+        while !sim.game_over {
+            assert!(sim.tick < 60, "the player was never eaten");
+            sim.step(&idle, TICK_DT);
+            if !sim.player().is_alive {
+                eaten_at.get_or_insert(sim.clock.elapsed);
+            }
+        }
+
+        let time = eaten_at.expect("the player was eaten");
+        let elimination = sim.player_elimination().expect("the elimination was recorded");
+        assert_eq!(elimination.killer_name.as_deref(), Some(killer.as_str()));
+        assert_eq!(elimination.time, time);
+        assert_eq!(sim.result, VictoryResult::PlayerEliminated { killer_name: Some(killer), time });
+        assert!(sim.events.contains(&GameEvent::HoleEliminated { hole_id: sim.player().id, by: Some(sim.holes[1].id) }));
+    }
+}
@@ -0,0 +1,103 @@
+//! Hole skins - the catalog of border patterns and border styles, and what unlocks them
+
+use ::rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::app::achievements::Achievement;
+use crate::app::profile::PlayerProfile;
+
+/// Requirement to use a catalog entry
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Unlock {
+    Free,
+    /// Reach a profile level
+    Level(u32),
+    /// Win this many matches in any mode
+    Wins(u32),
+    /// Eat this many holes over all matches
+    Eliminations(u64),
+    Achievement(Achievement),
+}
+
+impl Unlock {
+    pub fn is_met(&self, profile: &PlayerProfile) -> bool {
+        match *self {
+            Unlock::Free => true,
+            Unlock::Level(level) => profile.level() >= level,
+            Unlock::Wins(wins) => profile.wins() >= wins,
+            Unlock::Eliminations(count) => profile.eliminations >= count,
+            Unlock::Achievement(achievement) => profile.has_achievement(achievement),
+        }
+    }
+
+    /// What the player still has to do, shown on locked entries
+    pub fn description(&self) -> String {
+        match self {
+            Unlock::Free => String::new(),
+            Unlock::Level(level) => format!("Reach level {}", level),
+            Unlock::Wins(wins) => format!("Win {} matches", wins),
+            Unlock::Eliminations(count) => format!("Eat {} holes", count),
+            Unlock::Achievement(achievement) => format!("Unlock \"{}\"", achievement.name()),
+        }
+    }
+}
+
+/// One entry of the skin catalog
+pub struct SkinDef {
+    pub name: &'static str,
+    pub unlock: Unlock,
+}
+
+/// Border patterns, indexed by `Hole::skin_pattern`
+pub const PATTERNS: [SkinDef; 8] = [
+    SkinDef { name: "Solid", unlock: Unlock::Free },
+    SkinDef { name: "Stripes", unlock: Unlock::Free },
+    SkinDef { name: "Wave", unlock: Unlock::Level(2) },
+    SkinDef { name: "Dots", unlock: Unlock::Level(3) },
+    SkinDef { name: "Gradient", unlock: Unlock::Level(5) },
+    SkinDef { name: "Spiral", unlock: Unlock::Level(8) },
+    SkinDef { name: "Starfield", unlock: Unlock::Achievement(Achievement::BlackHole) },
+    SkinDef { name: "Rainbow", unlock: Unlock::Level(15) },
+];
+
+/// Border styles, indexed by `Hole::border_style`
+pub const BORDERS: [SkinDef; 4] = [
+    SkinDef { name: "Classic", unlock: Unlock::Free },
+    SkinDef { name: "Bold", unlock: Unlock::Level(2) },
+    SkinDef { name: "Neon", unlock: Unlock::Wins(10) },
+    SkinDef { name: "Double Ring", unlock: Unlock::Achievement(Achievement::TripleThreat) },
+];
+
+/// Pattern and border a hole is drawn with
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HoleSkin {
+    pub pattern: u8,
+    pub border: u8,
+}
+
+impl HoleSkin {
+    /// Any skin from the catalog, locked or not (used for bots)
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            pattern: rng.gen_range(0..PATTERNS.len()) as u8,
+            border: rng.gen_range(0..BORDERS.len()) as u8,
+        }
+    }
+
+    pub fn pattern_def(&self) -> Option<&'static SkinDef> {
+        PATTERNS.get(self.pattern as usize)
+    }
+
+    pub fn border_def(&self) -> Option<&'static SkinDef> {
+        BORDERS.get(self.border as usize)
+    }
+
+    /// The skin with unknown or still locked parts replaced by the defaults
+    pub fn unlocked_for(&self, profile: &PlayerProfile) -> Self {
+        let usable = |def: Option<&SkinDef>| def.is_some_and(|d| d.unlock.is_met(profile));
+        Self {
+            pattern: if usable(self.pattern_def()) { self.pattern } else { 0 },
+            border: if usable(self.border_def()) { self.border } else { 0 },
+        }
+    }
+}
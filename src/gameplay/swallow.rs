@@ -1,11 +1,12 @@
 //! Swallow/capture logic and animations
 
+use crate::gameplay::events::GameEvent;
 use crate::gameplay::hole::Hole;
-use crate::world::objects::{WorldObject, ObjectState};
+use crate::world::objects::{WorldObject, ObjectState, ObjectType};
 use crate::world::spatial::SpatialGrid;
-use crate::render::vfx::{VfxSystem, VfxType};
+use crate::render::vfx::VfxType;
 
-/// Growth multiplier for consumed objects
+/// Default growth multiplier for consumed objects
 pub const GROWTH_MULTIPLIER: f32 = 0.15;
 
 /// Process swallowing for a hole
@@ -13,7 +14,8 @@ pub fn process_swallow(
     hole: &mut Hole,
     objects: &mut [WorldObject],
     spatial: &SpatialGrid,
-    vfx: &mut VfxSystem,
+    effects: &mut Vec<VfxType>,
+    events: &mut Vec<GameEvent>,
 ) -> Vec<u32> {
     if !hole.is_alive {
         return vec![];
@@ -35,55 +37,84 @@ pub fn process_swallow(
         // Check if can capture
         if hole.can_capture_at(obj.x, obj.y, obj.size) {
             // Start falling animation
-            obj.start_falling(hole.x, hole.y);
+            obj.start_falling(hole.id, hole.x, hole.y);
             consumed_ids.push(obj.id);
+            events.push(GameEvent::ObjectSwallowed { hole_id: hole.id, object_id: obj.id, obj_type: obj.obj_type });
+            if obj.obj_type == ObjectType::Building {
+                events.push(GameEvent::BuildingSwallowed { hole_id: hole.id, object_id: obj.id });
+            }
             
-            // Spawn particles
-            let particle_count = (obj.size / 5.0).ceil() as usize;
-            vfx.spawn(VfxType::SwallowParticles {
-                x: obj.x,
-                y: obj.y,
-                color: obj.color,
-                count: particle_count.min(20),
-            });
-            
-            // Spawn ripple
-            vfx.spawn(VfxType::Ripple {
-                x: hole.x,
-                y: hole.y,
-                radius: hole.radius,
-                color: hole.color,
-            });
+            capture_effects(obj, hole, effects);
         }
     }
     
     consumed_ids
 }
 
-/// Update falling objects and apply growth
+/// Particles where the object was and a ripple on the hole that took it
+pub fn capture_effects(obj: &WorldObject, hole: &Hole, effects: &mut Vec<VfxType>) {
+    let particle_count = (obj.size / 5.0).ceil() as usize;
+    effects.push(VfxType::SwallowParticles {
+        x: obj.x,
+        y: obj.y,
+        color: obj.color,
+        count: particle_count.min(20),
+    });
+    effects.push(VfxType::Ripple {
+        x: hole.x,
+        y: hole.y,
+        radius: hole.radius,
+        color: hole.color,
+    });
+}
+
+/// Update falling objects and credit each finished one to the hole that captured it.
+/// Objects keep falling toward their captor as it moves; if the captor died meanwhile
+/// the object is still consumed but nobody grows from it.
 pub fn update_falling_objects(
-    hole: &mut Hole,
+    holes: &mut [Hole],
     objects: &mut [WorldObject],
     dt: f32,
+    growth_multiplier: f32,
 ) {
     for obj in objects.iter_mut() {
-        if matches!(obj.state, ObjectState::Falling { .. }) {
-            if obj.update_falling(dt) {
-                // Object finished falling, apply growth
-                hole.grow(obj.mass, GROWTH_MULTIPLIER);
+        let Some(captor_id) = obj.captor() else { continue };
+        let captor = holes.iter_mut().find(|h| h.id == captor_id && h.is_alive);
+        if let Some(ref hole) = captor {
+            obj.retarget_falling(hole.x, hole.y);
+        }
+        if obj.update_falling(dt) {
+            // Object finished falling, apply growth
+            if let Some(hole) = captor {
+                hole.grow(obj.mass, growth_multiplier);
+                if obj.obj_type == ObjectType::Building {
+                    hole.buildings_eaten += 1;
+                }
             }
         }
     }
 }
 
-/// Process hole vs hole combat
+/// Animate falling objects toward their captors without crediting anyone, for a mirror of a
+/// round whose hole sizes come from elsewhere (a network client)
+pub fn animate_falling_objects(holes: &[Hole], objects: &mut [WorldObject], dt: f32) {
+    for obj in objects.iter_mut() {
+        let Some(captor_id) = obj.captor() else { continue };
+        if let Some(hole) = holes.iter().find(|h| h.id == captor_id && h.is_alive) {
+            obj.retarget_falling(hole.x, hole.y);
+        }
+        obj.update_falling(dt);
+    }
+}
+
+/// Process hole vs hole combat, returns the (winner, loser) index pairs
 pub fn process_hole_combat(
     holes: &mut [Hole],
-    player_idx: usize,
-    vfx: &mut VfxSystem,
+    effects: &mut Vec<VfxType>,
+    events: &mut Vec<GameEvent>,
     allow_respawn: bool,
     respawn_time: f32,
-) -> Option<usize> {
+) -> Vec<(usize, usize)> {
     let mut eliminations: Vec<(usize, usize)> = Vec::new(); // (winner, loser)
     
     // Check all pairs
@@ -105,20 +136,18 @@ pub fn process_hole_combat(
         }
     }
     
-    let mut player_eliminated = None;
-    
     // Process eliminations
-    for (winner, loser) in eliminations {
+    for &(winner, loser) in &eliminations {
         // Spawn big VFX
         let loser_hole = &holes[loser];
-        vfx.spawn(VfxType::SwallowParticles {
+        effects.push(VfxType::SwallowParticles {
             x: loser_hole.x,
             y: loser_hole.y,
             color: loser_hole.color,
             count: 30,
         });
         
-        vfx.spawn(VfxType::Ripple {
+        effects.push(VfxType::Ripple {
             x: holes[winner].x,
             y: holes[winner].y,
             radius: holes[winner].radius * 1.5,
@@ -130,17 +159,62 @@ pub fn process_hole_combat(
         holes[winner].area += loser_area * 0.5;
         holes[winner].radius = (holes[winner].area / std::f32::consts::PI).sqrt();
         holes[winner].eliminations += 1;
+        events.push(GameEvent::HoleEliminated { hole_id: holes[loser].id, by: Some(holes[winner].id) });
         
         if allow_respawn {
             holes[loser].die(respawn_time);
         } else {
             holes[loser].is_alive = false;
         }
-        
-        if loser == player_idx {
-            player_eliminated = Some(winner);
-        }
     }
     
-    player_eliminated
+    eliminations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::rand::SeedableRng;
+    use ::rand::rngs::StdRng;
+    use macroquad::prelude::WHITE;
+
+    #[test]
+    fn falling_objects_are_credited_to_their_captor() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut holes = vec![
+            Hole::new(1, 100.0, 100.0, "A".to_string(), WHITE, true),
+            Hole::new(2, 900.0, 900.0, "B".to_string(), WHITE, false),
+        ];
+        // A heavy car under B, a light person under A
+        let mut objects = vec![
+            WorldObject::new(10, 100.0, 100.0, ObjectType::Person, &mut rng),
+            WorldObject::new(11, 900.0, 900.0, ObjectType::Car, &mut rng),
+        ];
+        let mut spatial = SpatialGrid::new();
+        spatial.build(&objects);
+        let mut effects = Vec::new();
+        let mut events = Vec::new();
+        let start_area = holes[0].area;
+
+        // Both holes capture in the same step
+        for hole in &mut holes {
+            process_swallow(hole, &mut objects, &spatial, &mut effects, &mut events);
+        }
+        assert_eq!(events.len(), 2);
+        assert_eq!(objects[0].captor(), Some(1));
+        assert_eq!(objects[1].captor(), Some(2));
+
+        // B moves away mid-fall, its object follows it
+        holes[1].x = 950.0;
+        for _ in 0..60 {
+            update_falling_objects(&mut holes, &mut objects, 1.0 / 60.0, GROWTH_MULTIPLIER);
+        }
+
+        assert!(objects.iter().all(|o| o.consumed));
+        assert_eq!(holes[0].score, 1);
+        assert_eq!(holes[1].score, 1);
+        assert_eq!(holes[0].area, start_area + objects[0].mass * GROWTH_MULTIPLIER);
+        assert_eq!(holes[1].area, start_area + objects[1].mass * GROWTH_MULTIPLIER);
+        assert!(objects[1].x > 900.0);
+    }
 }
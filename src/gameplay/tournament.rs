@@ -0,0 +1,388 @@
+//! Bot tournaments - seeded rounds of bots only, run headless as fast as the machine allows,
+//! to compare bot tunings on numbers instead of playtesting
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use serde::Serialize;
+use crate::gameplay::bots::{BotController, BotDifficulty, BotProfile};
+use crate::gameplay::modes::{GameMode, ModeRules};
+use crate::gameplay::simulation::Simulation;
+use crate::time::tick::TICK_DT;
+
+/// Two-sided 95% quantile of the normal distribution
+const Z_95: f32 = 1.96;
+
+/// A bot tuning taking part in a tournament
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Entrant {
+    pub name: String,
+    pub difficulty: BotDifficulty,
+    pub profile: BotProfile,
+}
+
+impl Entrant {
+    /// A tier as it ships
+    pub fn new(difficulty: BotDifficulty) -> Self {
+        Self { name: difficulty.name().to_string(), difficulty, profile: difficulty.profile() }
+    }
+
+    /// Parse `[NAME=]TIER[:key=value,...]`, a tier with some of its profile values changed,
+    /// e.g. `bold=Normal:aggression=0.9,threat_margin=1.5`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (head, overrides) = spec.split_once(':').unwrap_or((spec, ""));
+        let (name, tier) = head.split_once('=').unwrap_or((head, head));
+        let difficulty = BotDifficulty::from_name(tier).ok_or_else(|| format!("Unknown bot tier {}", tier))?;
+        let mut entrant = Self { name: name.to_string(), difficulty, profile: difficulty.profile() };
+        for pair in overrides.split(',').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').ok_or_else(|| format!("Expected key=value, got {}", pair))?;
+            let value = value.parse().map_err(|_| format!("{} expects a number, got {}", key, value))?;
+            *profile_value(&mut entrant.profile, key)? = value;
+        }
+        Ok(entrant)
+    }
+}
+
+/// Names of the `BotProfile` values an entrant can change
+pub const PROFILE_KEYS: [&str; 8] = [
+    "perception", "reaction_delay", "steering_noise", "dash_usage",
+    "aggression", "mistake_rate", "threat_margin", "mass_weight",
+];
+
+fn profile_value<'a>(profile: &'a mut BotProfile, key: &str) -> Result<&'a mut f32, String> {
+    Ok(match key {
+        "perception" => &mut profile.perception,
+        "reaction_delay" => &mut profile.reaction_delay,
+        "steering_noise" => &mut profile.steering_noise,
+        "dash_usage" => &mut profile.dash_usage,
+        "aggression" => &mut profile.aggression,
+        "mistake_rate" => &mut profile.mistake_rate,
+        "threat_margin" => &mut profile.threat_margin,
+        "mass_weight" => &mut profile.mass_weight,
+        _ => return Err(format!("Unknown bot parameter {} (one of {})", key, PROFILE_KEYS.join(", "))),
+    })
+}
+
+/// How one bot did in one round
+#[derive(Clone, Debug, PartialEq)]
+pub struct BotRound {
+    /// Index into the tournament's entrants
+    pub entrant: usize,
+    /// Radius at the end, back to the starting size if the bot was out
+    pub radius: f32,
+    pub peak_radius: f32,
+    pub eliminations: i32,
+    pub objects: i32,
+    /// Seconds until the bot was first eaten, the whole round if it never was
+    pub survival: f32,
+    /// Final place, 1 is best; bots that are out come after the ones still in, latest out first
+    pub rank: usize,
+}
+
+/// A series of rounds between bot tunings
+#[derive(Clone, Debug)]
+pub struct Tournament {
+    /// Rules of every round, `bot_count` bots take part
+    pub rules: ModeRules,
+    pub entrants: Vec<Entrant>,
+    /// Seed of the first round, the following rounds count up from it
+    pub seed: u64,
+    pub rounds: u32,
+}
+
+impl Tournament {
+    pub fn new(rules: ModeRules, entrants: Vec<Entrant>, seed: u64, rounds: u32) -> Self {
+        assert!(!entrants.is_empty(), "a tournament needs entrants");
+        Self { rules, entrants, seed, rounds }
+    }
+
+    /// Entrant driving bot `bot` of round `round`; the lineup turns every round
+    /// so no entrant keeps the same spot
+    pub fn entrant_of(&self, round: u32, bot: usize) -> usize {
+        (bot + round as usize) % self.entrants.len()
+    }
+
+    /// Play one round to its end without any rendering or waiting
+    pub fn play_round(&self, round: u32) -> Vec<BotRound> {
+        let mut sim = Simulation::with_players(self.rules.clone(), &[], self.seed.wrapping_add(round as u64));
+        for (bot, controller) in sim.bot_controllers.iter_mut().enumerate() {
+            let entrant = &self.entrants[self.entrant_of(round, bot)];
+            *controller = BotController::new(entrant.difficulty);
+            controller.profile = entrant.profile;
+        }
+        sim.start();
+
+        let count = sim.holes.len();
+        let last_standing = !sim.mode_rules.mode.allows_respawn();
+        let mut alive = vec![true; count];
+        let mut first_out: Vec<Option<f32>> = vec![None; count];
+        let mut last_out = vec![0.0; count];
+        while !sim.game_over {
+            sim.step(&[], TICK_DT);
+            // Nobody watches
+            sim.effects.clear();
+            sim.events.clear();
+            for (i, hole) in sim.holes.iter().enumerate() {
+                if alive[i] && !hole.is_alive {
+                    first_out[i].get_or_insert(sim.clock.elapsed);
+                    last_out[i] = sim.clock.elapsed;
+                }
+                alive[i] = hole.is_alive;
+            }
+            // Without humans nobody can win a battle, it is over once one bot is left
+            if last_standing && alive.iter().filter(|a| **a).count() <= 1 {
+                break;
+            }
+        }
+
+        let holes = &sim.holes;
+        let out_time = |i: usize| if holes[i].is_alive { f32::MAX } else { last_out[i] };
+        let mut order: Vec<usize> = (0..count).collect();
+        order.sort_by(|&a, &b| {
+            out_time(b).total_cmp(&out_time(a))
+                .then(holes[b].radius.total_cmp(&holes[a].radius))
+                .then(holes[b].peak_radius.total_cmp(&holes[a].peak_radius))
+        });
+        let mut rank = vec![0; count];
+        for (place, &i) in order.iter().enumerate() {
+            rank[i] = place + 1;
+        }
+
+        holes.iter().enumerate().map(|(i, hole)| BotRound {
+            entrant: self.entrant_of(round, i),
+            radius: hole.radius,
+            peak_radius: hole.peak_radius,
+            eliminations: hole.eliminations,
+            objects: hole.score,
+            survival: first_out[i].unwrap_or(sim.clock.elapsed),
+            rank: rank[i],
+        }).collect()
+    }
+
+    /// Play every round on up to `jobs` threads, calling `progress` with the number of rounds
+    /// finished so far. The report does not depend on the number of threads.
+    pub fn run(&self, jobs: usize, progress: impl Fn(u32) + Sync) -> TournamentReport {
+        let next = AtomicU32::new(0);
+        let done = AtomicU32::new(0);
+        let results = Mutex::new(vec![Vec::new(); self.rounds as usize]);
+        std::thread::scope(|scope| {
+            for _ in 0..jobs.clamp(1, self.rounds.max(1) as usize) {
+                scope.spawn(|| loop {
+                    let round = next.fetch_add(1, Ordering::Relaxed);
+                    if round >= self.rounds {
+                        break;
+                    }
+                    let bots = self.play_round(round);
+                    results.lock().expect("a round thread panicked")[round as usize] = bots;
+                    progress(done.fetch_add(1, Ordering::Relaxed) + 1);
+                });
+            }
+        });
+        TournamentReport::new(self, &results.into_inner().expect("a round thread panicked"))
+    }
+}
+
+/// Mean of a sample with its 95% confidence interval
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Estimate {
+    pub mean: f32,
+    pub low: f32,
+    pub high: f32,
+}
+
+impl Estimate {
+    /// Normal approximation, sound for the hundreds of samples a tournament gives
+    pub fn of(samples: &[f32]) -> Self {
+        let n = samples.len() as f32;
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mean = samples.iter().sum::<f32>() / n;
+        let variance = if samples.len() > 1 {
+            samples.iter().map(|s| (s - mean).powi(2)).sum::<f32>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        let margin = Z_95 * (variance / n).sqrt();
+        Self { mean, low: mean - margin, high: mean + margin }
+    }
+
+    /// Share of `successes` in `trials` with its Wilson score interval, which unlike the normal
+    /// approximation stays within 0..=1 and does not collapse for shares near 0 or 1
+    pub fn proportion(successes: usize, trials: usize) -> Self {
+        if trials == 0 {
+            return Self::default();
+        }
+        let n = trials as f32;
+        let share = successes as f32 / n;
+        let z2 = Z_95 * Z_95;
+        let scale = 1.0 + z2 / n;
+        let center = (share + z2 / (2.0 * n)) / scale;
+        let margin = Z_95 * (share * (1.0 - share) / n + z2 / (4.0 * n * n)).sqrt() / scale;
+        Self { mean: share, low: (center - margin).max(0.0), high: (center + margin).min(1.0) }
+    }
+
+    /// Keep the bounds at or above the smallest value the stat can take
+    pub fn at_least(self, min: f32) -> Self {
+        Self { low: self.low.max(min), high: self.high.max(min), ..self }
+    }
+}
+
+/// Every stat of one entrant over the tournament
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EntrantReport {
+    pub name: String,
+    pub difficulty: BotDifficulty,
+    pub profile: BotProfile,
+    /// Bot rounds played, more than the rounds when the entrant fields several bots per round
+    pub samples: usize,
+    pub radius: Estimate,
+    pub peak_radius: Estimate,
+    pub eliminations: Estimate,
+    pub objects: Estimate,
+    pub survival: Estimate,
+    pub rank: Estimate,
+    /// Share of bot rounds finished first
+    pub win_rate: Estimate,
+    /// `rank_counts[r]` is how often the entrant finished at rank `r + 1`
+    pub rank_counts: Vec<u32>,
+}
+
+/// Outcome of a tournament
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TournamentReport {
+    pub mode: GameMode,
+    pub seed: u64,
+    pub rounds: u32,
+    pub bots: usize,
+    pub round_duration: f32,
+    pub entrants: Vec<EntrantReport>,
+}
+
+impl TournamentReport {
+    fn new(tournament: &Tournament, rounds: &[Vec<BotRound>]) -> Self {
+        let bots = tournament.rules.bot_count;
+        let entrants = tournament.entrants.iter().enumerate().map(|(e, entrant)| {
+            let played: Vec<&BotRound> = rounds.iter().flatten().filter(|b| b.entrant == e).collect();
+            // Every stat has a floor the interval must not cross: 0 for sizes and counts, 1 for ranks
+            let stat = |f: fn(&BotRound) -> f32, min: f32| Estimate::of(&played.iter().map(|b| f(b)).collect::<Vec<_>>()).at_least(min);
+            let mut rank_counts = vec![0; bots];
+            for bot in &played {
+                rank_counts[bot.rank - 1] += 1;
+            }
+            EntrantReport {
+                name: entrant.name.clone(),
+                difficulty: entrant.difficulty,
+                profile: entrant.profile,
+                samples: played.len(),
+                radius: stat(|b| b.radius, 0.0),
+                peak_radius: stat(|b| b.peak_radius, 0.0),
+                eliminations: stat(|b| b.eliminations as f32, 0.0),
+                objects: stat(|b| b.objects as f32, 0.0),
+                survival: stat(|b| b.survival, 0.0),
+                rank: stat(|b| b.rank as f32, 1.0),
+                win_rate: Estimate::proportion(played.iter().filter(|b| b.rank == 1).count(), played.len()),
+                rank_counts,
+            }
+        }).collect();
+        Self {
+            mode: tournament.rules.mode,
+            seed: tournament.seed,
+            rounds: tournament.rounds,
+            bots,
+            round_duration: tournament.rules.round_duration,
+            entrants,
+        }
+    }
+
+    /// One row per entrant: mean, low and high of every stat, then the rank counts
+    pub fn to_csv(&self) -> String {
+        const STATS: [&str; 7] = ["radius", "peak_radius", "eliminations", "objects", "survival", "rank", "win_rate"];
+        let mut header = vec!["name".to_string(), "difficulty".to_string(), "samples".to_string()];
+        for stat in STATS {
+            header.extend([format!("{}_mean", stat), format!("{}_low", stat), format!("{}_high", stat)]);
+        }
+        header.extend((1..=self.bots).map(|r| format!("rank_{}", r)));
+
+        let mut csv = header.join(",") + "\n";
+        for entrant in &self.entrants {
+            let mut row = vec![
+                format!("\"{}\"", entrant.name.replace('"', "\"\"")),
+                entrant.difficulty.name().to_string(),
+                entrant.samples.to_string(),
+            ];
+            let stats = [
+                entrant.radius, entrant.peak_radius, entrant.eliminations, entrant.objects,
+                entrant.survival, entrant.rank, entrant.win_rate,
+            ];
+            for stat in stats {
+                row.extend([stat.mean, stat.low, stat.high].map(|v| format!("{:.3}", v)));
+            }
+            row.extend(entrant.rank_counts.iter().map(|c| c.to_string()));
+            csv += &(row.join(",") + "\n");
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+
+    #[test]
+    fn entrants_parse_tier_name_and_overrides() {
+        let bold = Entrant::parse("bold=normal:aggression=0.9,threat_margin=1.5").unwrap();
+        assert_eq!(bold.name, "bold");
+        assert_eq!(bold.difficulty, BotDifficulty::Normal);
+        assert_eq!(bold.profile.aggression, 0.9);
+        assert_eq!(bold.profile.threat_margin, 1.5);
+        assert_eq!(bold.profile.perception, BotDifficulty::Normal.profile().perception);
+        assert_eq!(Entrant::parse("Hard").unwrap(), Entrant::new(BotDifficulty::Hard));
+        assert!(Entrant::parse("Expert").is_err());
+        assert!(Entrant::parse("Hard:courage=2").is_err());
+    }
+
+    #[test]
+    fn intervals_stay_within_what_the_stat_can_take() {
+        // A win in none of eight rounds is no proof the entrant never wins
+        let never = Estimate::proportion(0, 8);
+        assert_eq!((never.mean, never.low), (0.0, 0.0));
+        assert!((never.high - 0.324).abs() < 1e-3);
+        let always = Estimate::proportion(8, 8);
+        assert_eq!((always.mean, always.high), (1.0, 1.0));
+        assert!((always.low - 0.676).abs() < 1e-3);
+        let half = Estimate::proportion(4, 8);
+        assert!(half.low > 0.0 && (half.low + half.high - 1.0).abs() < 1e-5);
+        assert_eq!(Estimate::proportion(0, 0), Estimate::default());
+
+        // Mostly zeros with one large value spreads the normal interval below zero
+        let eliminations = Estimate::of(&[0.0, 0.0, 0.0, 3.0]).at_least(0.0);
+        assert_eq!(eliminations.low, 0.0);
+        assert_eq!(eliminations.mean, 0.75);
+        assert!(eliminations.high > 0.75);
+    }
+
+    #[test]
+    fn reports_are_reproducible_whatever_the_thread_count() {
+        let mut rules = ModeRules::new(GameMode::Battle, &Settings::default());
+        rules.bot_count = 4;
+        rules.round_duration = 5.0;
+        let entrants = vec![Entrant::new(BotDifficulty::Easy), Entrant::new(BotDifficulty::Nightmare)];
+        let tournament = Tournament::new(rules, entrants, 3, 4);
+
+        let report = tournament.run(1, |_| {});
+        assert_eq!(report, tournament.run(3, |_| {}));
+        for entrant in &report.entrants {
+            // Two bots per entrant in each of the four rounds
+            assert_eq!(entrant.samples, 8);
+            assert_eq!(entrant.rank_counts.iter().sum::<u32>(), 8);
+            assert!(entrant.survival.mean <= 5.0 + 1e-3);
+            let stats = [entrant.radius, entrant.peak_radius, entrant.eliminations, entrant.objects, entrant.survival];
+            assert!(stats.iter().all(|s| s.low >= 0.0 && s.low <= s.mean && s.mean <= s.high));
+            assert!(entrant.rank.low >= 1.0);
+            assert!(0.0 <= entrant.win_rate.low && entrant.win_rate.high <= 1.0);
+        }
+        let winners: u32 = report.entrants.iter().map(|e| e.rank_counts[0]).sum();
+        assert_eq!(winners, 4);
+    }
+}
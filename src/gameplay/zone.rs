@@ -0,0 +1,199 @@
+//! Battle safe zone - a circle that shrinks in phases, holes outside it lose area
+
+use macroquad::prelude::*;
+use ::rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// One zone phase as fractions of the round: wait, then shrink to a fraction of the start radius
+struct ZonePhase {
+    wait: f32,
+    shrink: f32,
+    radius: f32,
+}
+
+const ZONE_PHASES: [ZonePhase; 5] = [
+    ZonePhase { wait: 0.10, shrink: 0.08, radius: 0.70 },
+    ZonePhase { wait: 0.08, shrink: 0.08, radius: 0.45 },
+    ZonePhase { wait: 0.07, shrink: 0.07, radius: 0.25 },
+    ZonePhase { wait: 0.06, shrink: 0.06, radius: 0.10 },
+    ZonePhase { wait: 0.05, shrink: 0.05, radius: 0.0 },
+];
+
+/// Fraction of its area a hole loses per second outside the zone
+pub const ZONE_AREA_LOSS: f32 = 0.08;
+/// Flat area lost per second outside the zone, so small holes are not spared
+pub const ZONE_AREA_LOSS_MIN: f32 = 150.0;
+
+/// Safe zone state
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SafeZone {
+    pub center: Vec2,
+    pub radius: f32,
+    /// Circle the zone is shrinking (or about to shrink) toward
+    pub target_center: Vec2,
+    pub target_radius: f32,
+    /// Current phase, equal to the phase count once the zone is fully closed
+    pub phase: usize,
+    /// Seconds left in the current wait or shrink
+    pub timer: f32,
+    pub shrinking: bool,
+    start_center: Vec2,
+    start_radius: f32,
+    initial_radius: f32,
+    round_duration: f32,
+}
+
+impl SafeZone {
+    /// Zone covering the whole world; the first target is rolled right away
+    pub fn new(world_width: f32, world_height: f32, round_duration: f32, rng: &mut impl Rng) -> Self {
+        let center = vec2(world_width / 2.0, world_height / 2.0);
+        let radius = center.length();
+        let mut zone = Self {
+            center,
+            radius,
+            target_center: center,
+            target_radius: radius,
+            phase: 0,
+            timer: 0.0,
+            shrinking: false,
+            start_center: center,
+            start_radius: radius,
+            initial_radius: radius,
+            round_duration,
+        };
+        zone.begin_phase(rng);
+        zone
+    }
+
+    /// All phases are over
+    pub fn is_closed(&self) -> bool {
+        self.phase >= ZONE_PHASES.len()
+    }
+
+    /// Advance the zone by `dt` seconds
+    pub fn update(&mut self, dt: f32, rng: &mut impl Rng) {
+        if self.is_closed() { return; }
+        self.timer -= dt;
+
+        if self.shrinking {
+            let duration = ZONE_PHASES[self.phase].shrink * self.round_duration;
+            let t = (1.0 - self.timer / duration).clamp(0.0, 1.0);
+            self.center = self.start_center.lerp(self.target_center, t);
+            self.radius = self.start_radius + (self.target_radius - self.start_radius) * t;
+            if self.timer <= 0.0 {
+                self.center = self.target_center;
+                self.radius = self.target_radius;
+                self.phase += 1;
+                if !self.is_closed() {
+                    self.begin_phase(rng);
+                }
+            }
+        } else if self.timer <= 0.0 {
+            self.shrinking = true;
+            self.start_center = self.center;
+            self.start_radius = self.radius;
+            self.timer = ZONE_PHASES[self.phase].shrink * self.round_duration;
+        }
+    }
+
+    /// Roll the next circle inside the current one and start waiting
+    fn begin_phase(&mut self, rng: &mut impl Rng) {
+        let phase = &ZONE_PHASES[self.phase];
+        self.target_radius = self.initial_radius * phase.radius;
+        let max_offset = (self.radius - self.target_radius).max(0.0);
+        let angle = rng.gen::<f32>() * std::f32::consts::TAU;
+        let offset = rng.gen::<f32>().sqrt() * max_offset;
+        self.target_center = self.center + Vec2::from_angle(angle) * offset;
+        self.shrinking = false;
+        self.timer = phase.wait * self.round_duration;
+    }
+
+    /// Whether a point is inside the current zone
+    pub fn contains(&self, p: Vec2) -> bool {
+        p.distance(self.center) <= self.radius
+    }
+
+    /// Distance from a point to the zone edge, negative inside
+    pub fn distance_outside(&self, p: Vec2) -> f32 {
+        p.distance(self.center) - self.radius
+    }
+
+    /// Area a hole of `area` loses over `dt` seconds outside the zone
+    pub fn area_loss(area: f32, dt: f32) -> f32 {
+        (area * ZONE_AREA_LOSS + ZONE_AREA_LOSS_MIN) * dt
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::events::GameEvent;
+    use crate::gameplay::modes::{GameMode, ModeRules};
+    use crate::gameplay::simulation::{HoleInput, PlayerSlot, Simulation, SimRng};
+    use crate::gameplay::skins::HoleSkin;
+    use crate::time::tick::TICK_DT;
+
+    #[test]
+    fn the_zone_shrinks_through_every_phase_into_its_own_circle() {
+        let mut rng = SimRng::seed_from_u64(11);
+        let mut zone = SafeZone::new(3000.0, 2000.0, 100.0, &mut rng);
+        let start_radius = zone.radius;
+        let mut circle = (zone.center, zone.radius);
+        let mut target = (zone.target_center, zone.target_radius);
+        let mut phases = vec![zone.phase];
+        let mut elapsed = 0.0;
+        while !zone.is_closed() {
+            assert!(elapsed < 100.0, "the zone never closed");
+            // Each new circle is rolled inside the one the zone has just shrunk to
+            assert!(target.0.distance(circle.0) + target.1 <= circle.1 + 1e-2);
+            let radius = zone.radius;
+            zone.update(TICK_DT, &mut rng);
+            elapsed += TICK_DT;
+            assert!(zone.radius <= radius + 1e-3, "the zone grew from {} to {}", radius, zone.radius);
+            if zone.phase != *phases.last().unwrap() {
+                phases.push(zone.phase);
+                circle = (zone.center, zone.radius);
+                target = (zone.target_center, zone.target_radius);
+                assert_eq!(zone.radius, start_radius * ZONE_PHASES[zone.phase - 1].radius);
+            }
+        }
+        assert_eq!(phases, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(zone.radius, 0.0);
+        let total: f32 = ZONE_PHASES.iter().map(|p| p.wait + p.shrink).sum();
+        assert!((elapsed - total * 100.0).abs() < 0.5, "closed after {}s", elapsed);
+    }
+
+    #[test]
+    fn holes_left_outside_the_zone_are_eliminated_by_nobody() {
+        let mut rules = ModeRules::new(GameMode::Battle, &Settings::default());
+        rules.bot_count = 0;
+        rules.round_duration = 60.0;
+        // Two humans parked in opposite corners, far from each other and soon outside the zone;
+        // the second one is much larger so the round is still on when the first one goes
+        let players = [PlayerSlot::new("Ana", HoleSkin::default()), PlayerSlot::new("Bia", HoleSkin::default())];
+        let mut sim = Simulation::with_players(rules, &players, 9);
+        let (width, height) = (sim.world.width, sim.world.height);
+        sim.holes[0].x = 0.0;
+        sim.holes[0].y = 0.0;
+        let bia = &mut sim.holes[1];
+        bia.x = width;
+        bia.y = height;
+        bia.area *= 50.0;
+        bia.radius = (bia.area / std::f32::consts::PI).sqrt();
+        sim.start();
+
+        let parked = [HoleInput { move_dir: Vec2::ZERO, dash: false }; 2];
+        while sim.player().is_alive {
+            assert!(!sim.game_over, "the round ended with the player still in");
+            sim.step(&parked, TICK_DT);
+        }
+
+        let player_id = sim.player().id;
+        assert!(!sim.zone.as_ref().unwrap().contains(sim.player().position()));
+        assert!(sim.events.contains(&GameEvent::HoleEliminated { hole_id: player_id, by: None }));
+        let elimination = sim.player_elimination().expect("the player was eliminated");
+        assert_eq!(elimination.killer_name, None);
+        assert_eq!(elimination.time, sim.clock.elapsed);
+    }
+}
@@ -0,0 +1,9 @@
+//! Hole.io Clone - game library
+//! Simulation, world generation and rendering shared by the game binaries
+
+pub mod app;
+pub mod world;
+pub mod gameplay;
+pub mod render;
+pub mod time;
+pub mod net;
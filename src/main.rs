@@ -3,119 +3,181 @@
 
 use macroquad::prelude::*;
 use ::rand::prelude::*;
-use ::rand::rngs::StdRng;
-use ::rand::SeedableRng;
-
-mod app;
-mod world;
-mod gameplay;
-mod render;
-mod time;
-
-use app::state::{AppState, GameState};
-use app::settings::Settings;
-use world::gen::World;
-use world::spatial::SpatialGrid;
-use gameplay::hole::Hole;
-use gameplay::modes::{GameMode, ModeRules};
-use gameplay::bots::{BotController, BOT_NAMES, get_bot_color};
-use gameplay::scoring::Leaderboard;
-use gameplay::swallow;
-use render::theme::Theme;
-use render::vfx::VfxSystem;
-use time::clock::GameClock;
-
-/// Camera state
-struct Camera {
-    x: f32,
-    y: f32,
-    zoom: f32,
-    target_zoom: f32,
-}
-
-impl Camera {
-    fn new() -> Self {
-        Self { x: 0.0, y: 0.0, zoom: 1.0, target_zoom: 1.0 }
-    }
-
-    fn follow(&mut self, target_x: f32, target_y: f32, hole_radius: f32, _dt: f32, smoothing: f32) {
-        let sw = screen_width();
-        let sh = screen_height();
-        
-        // Target camera position (center on hole)
-        let target_cx = target_x - sw / (2.0 * self.zoom);
-        let target_cy = target_y - sh / (2.0 * self.zoom);
-        
-        // Smooth follow
-        self.x += (target_cx - self.x) * smoothing.min(1.0);
-        self.y += (target_cy - self.y) * smoothing.min(1.0);
-        
-        // Dynamic zoom based on hole size
-        self.target_zoom = (50.0 / hole_radius).clamp(0.4, 1.2);
-        self.zoom += (self.target_zoom - self.zoom) * 0.02;
-    }
-}
-
-/// Complete game session
+
+use holeio_modern::app::state::{AppState, GameState, MenuItem};
+use holeio_modern::app::settings::{Settings, SettingId, settings_path};
+use holeio_modern::app::achievements::{Achievement, AchievementTracker};
+use holeio_modern::app::daily_history::{DailyHistory, daily_history_path};
+use holeio_modern::app::gamepad::Gamepad;
+use holeio_modern::app::input::{Binding, InputFrame, InputSource, MenuAction};
+use holeio_modern::app::highscores::{HighScoreEntry, HighScoreField, HighScores, TableKey, highscores_path};
+use holeio_modern::app::lobby::{LobbyField, MatchSetup};
+use holeio_modern::app::text_input::{DEFAULT_NAME, OnScreenKeyboard};
+use holeio_modern::app::profile::{MatchSummary, PlayerProfile, XpGain, profile_path};
+use holeio_modern::gameplay::daily::DailyChallenge;
+use holeio_modern::gameplay::modes::{GameMode, ModeRules};
+use holeio_modern::gameplay::replay::{self, Replay, ReplayPlayer};
+use holeio_modern::gameplay::savegame::{self, SavedSession};
+use holeio_modern::gameplay::simulation::{HoleInput, PlayerSlot, Simulation};
+use holeio_modern::gameplay::skins::{self, HoleSkin};
+use holeio_modern::net::client::{ClientEvent, ClientStatus, NetClient};
+use holeio_modern::net::protocol::DEFAULT_PORT;
+use holeio_modern::net::server::{Server, ServerConfig};
+use holeio_modern::net::transport::LinkConditions;
+use holeio_modern::render;
+use holeio_modern::render::camera::Camera;
+use holeio_modern::render::theme::Theme;
+use holeio_modern::render::vfx::VfxSystem;
+use holeio_modern::render::viewport;
+use holeio_modern::time::date::Date;
+use holeio_modern::time::tick::{FixedTimestep, TICK_DT, TICK_RATE};
+
+/// Playback speeds offered by the replay viewer
+const REPLAY_SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+/// Ticks skipped by one seek step (5 seconds)
+const REPLAY_SEEK_TICKS: u64 = 5 * TICK_RATE as u64;
+/// Free camera pan speed in screen pixels per second
+const FREE_CAMERA_SPEED: f32 = 600.0;
+/// Seconds an achievement toast stays on screen
+const TOAST_DURATION: f32 = 3.5;
+/// Results screen entries of a local round
+const RESULTS_OPTIONS: [&str; 4] = ["PLAY AGAIN", "WATCH REPLAY", "CHANGE MODE", "MAIN MENU"];
+/// Players a `--host` server waits for when `--players` is not given
+const DEFAULT_HOST_PLAYERS: usize = 2;
+
+/// Achievement unlocked during the round, shown in the HUD
+struct Toast {
+    achievement: Achievement,
+    age: f32,
+}
+
+/// Complete game session: the simulation plus its presentation state
 struct GameSession {
-    world: World,
-    spatial: SpatialGrid,
-    holes: Vec<Hole>,
-    bot_controllers: Vec<BotController>,
-    player_idx: usize,
-    clock: GameClock,
-    leaderboard: Leaderboard,
-    mode_rules: ModeRules,
+    sim: Simulation,
     vfx: VfxSystem,
-    camera: Camera,
-    game_over: bool,
+    /// One camera per human; with several the screen is split between them
+    cameras: Vec<Camera>,
+    timestep: FixedTimestep,
+    /// Dash pressed on a frame that has not reached a tick yet, per human
+    pending_dash: Vec<bool>,
+    /// Devices of each human, unused when playing alone
+    bindings: Vec<Binding>,
+    /// Inputs recorded so far
+    replay: Replay,
     results_time: f32,
+    /// XP the finished round added to the profile
+    xp_gain: Option<XpGain>,
+    /// Daily challenge score of the finished round and whether it was the day's best
+    daily_score: Option<(u32, bool)>,
+    achievements: AchievementTracker,
+    /// Unlocks waiting to be shown, the first one is on screen
+    toasts: Vec<Toast>,
 }
 
 impl GameSession {
-    fn new(mode: GameMode, player_name: &str, seed: u64) -> Self {
-        let mut rng = StdRng::seed_from_u64(seed);
-        let world = World::generate(seed);
-        let mode_rules = ModeRules::new(mode);
-        
-        // Create player
-        let player_pos = world.get_spawn_position(&mut rng);
-        let player = Hole::new_player(player_pos.x, player_pos.y, player_name.to_string());
-        
-        let mut holes = vec![player];
-        let mut bot_controllers = vec![BotController::default()]; // Placeholder for player
-        
-        // Create bots
-        for i in 0..mode_rules.bot_count {
-            let pos = world.get_spawn_position(&mut rng);
-            let name = BOT_NAMES[i % BOT_NAMES.len()].to_string();
-            let color = get_bot_color(i);
-            holes.push(Hole::new_bot(pos.x, pos.y, name, color));
-            bot_controllers.push(BotController::default());
-        }
-        
-        let mut spatial = SpatialGrid::new();
-        spatial.build(&world.objects);
-        
-        let clock = GameClock::new(mode.round_duration());
-        
+    fn new(rules: &ModeRules, players: &[PlayerSlot], bindings: &[Binding], seed: u64) -> Self {
+        let sim = Simulation::with_players(rules.clone(), players, seed);
         Self {
-            world,
-            spatial,
-            holes,
-            bot_controllers,
-            player_idx: 0,
-            clock,
-            leaderboard: Leaderboard::new(),
-            mode_rules,
-            vfx: VfxSystem::new(),
-            camera: Camera::new(),
-            game_over: false,
+            achievements: AchievementTracker::new(sim.player().id),
+            toasts: Vec::new(),
+            sim,
+            vfx: VfxSystem::new(seed),
+            cameras: players.iter().map(|_| Camera::new()).collect(),
+            timestep: FixedTimestep::new(),
+            pending_dash: vec![false; players.len()],
+            bindings: bindings.to_vec(),
+            replay: Replay::new(seed, rules, players),
             results_time: 0.0,
+            xp_gain: None,
+            daily_score: None,
+        }
+    }
+
+    /// Session resumed from a save file
+    fn from_saved(saved: SavedSession) -> Self {
+        let humans = saved.sim.human_count;
+        Self {
+            vfx: VfxSystem::new(saved.sim.seed),
+            achievements: saved.achievements,
+            toasts: Vec::new(),
+            sim: saved.sim,
+            cameras: (0..humans).map(|_| Camera::new()).collect(),
+            timestep: FixedTimestep::new(),
+            pending_dash: vec![false; humans],
+            bindings: saved.bindings,
+            replay: saved.replay,
+            results_time: 0.0,
+            xp_gain: None,
+            daily_score: None,
+        }
+    }
+
+    /// Suspend the round to disk, returns false if it could not be written
+    fn save(self, daily: Option<Date>) -> bool {
+        let path = savegame::save_path();
+        let saved = SavedSession { sim: self.sim, replay: self.replay, daily, achievements: self.achievements, bindings: self.bindings };
+        match saved.save(&path) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!("Could not save session {}: {}", path.display(), e);
+                false
+            }
         }
     }
 }
 
+/// Replay playback with viewer controls
+struct ReplayViewer {
+    player: ReplayPlayer,
+    vfx: VfxSystem,
+    camera: Camera,
+    timestep: FixedTimestep,
+    paused: bool,
+    speed_idx: usize,
+    /// Hole the camera follows (player or any bot)
+    follow_idx: usize,
+    /// Camera detached from `Camera::follow`
+    free_camera: bool,
+}
+
+impl ReplayViewer {
+    fn new(replay: Replay) -> Self {
+        Self {
+            vfx: VfxSystem::new(replay.seed),
+            player: ReplayPlayer::new(replay),
+            camera: Camera::new(),
+            timestep: FixedTimestep::new(),
+            paused: false,
+            speed_idx: 2, // 1x
+            follow_idx: 0,
+            free_camera: false,
+        }
+    }
+
+    fn speed(&self) -> f32 {
+        REPLAY_SPEEDS[self.speed_idx]
+    }
+
+    fn seek(&mut self, tick: u64) {
+        self.player.seek(tick, TICK_DT);
+        self.vfx.clear();
+    }
+}
+
+/// Round mirrored from a LAN server, with the presentation state around it
+struct NetGame {
+    client: NetClient,
+    vfx: VfxSystem,
+    camera: Camera,
+    results_time: f32,
+}
+
+impl NetGame {
+    fn new(client: NetClient) -> Self {
+        Self { client, vfx: VfxSystem::new(0), camera: Camera::new(), results_time: 0.0 }
+    }
+}
+
 fn window_conf() -> Conf {
     Conf {
         window_title: "Hole.io".to_owned(),
@@ -130,48 +192,173 @@ fn window_conf() -> Conf {
 #[macroquad::main(window_conf)]
 async fn main() {
     let mut app_state = AppState::default();
-    let mut settings = Settings::default();
+    let mut settings = Settings::load(&settings_path());
+    let mut profile = PlayerProfile::load(&profile_path());
+    let mut daily_history = DailyHistory::load(&daily_history_path());
+    let mut high_scores = HighScores::load(&highscores_path());
+    app_state.player_skin = settings.skin.unlocked_for(&profile);
+    if settings.player_name.is_empty() {
+        // First run: ask for a name before the menu
+        app_state.edit_name(GameState::Menu);
+    } else {
+        app_state.player_name = settings.player_name.clone();
+    }
     let mut theme = Theme::default();
+    theme.set_palette_index(settings.theme_index);
     let mut session: Option<GameSession> = None;
+    let mut viewer: Option<ReplayViewer> = None;
+    app_state.has_saved_session = savegame::save_path().exists();
     let mut animation_time = 0.0f32;
     let mut rng = ::rand::thread_rng();
+    let mut keyboard = KeyboardInput;
+    let mut gamepads = Gamepad::open_all(settings.gamepad_deadzone);
+
+    // Command line: host or join a LAN game, or play back a replay file right away
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut net: Option<NetGame> = None;
+    let server = match args.first().map(String::as_str) {
+        Some("--host") => host_game(&args[1..], &settings, &mut rng),
+        Some("--connect") => {
+            let addr = args.get(1).and_then(|a| server_address(a));
+            if addr.is_none() {
+                eprintln!("Usage: --connect HOST[:PORT]");
+            }
+            addr
+        }
+        Some(path) => {
+            match Replay::load(std::path::Path::new(path)) {
+                Ok(replay) => {
+                    app_state.selected_mode = replay.rules.mode;
+                    viewer = Some(ReplayViewer::new(replay));
+                    app_state.transition_to(GameState::Replay);
+                }
+                Err(e) => eprintln!("Could not load replay {}: {}", path, e),
+            }
+            None
+        }
+        None => None,
+    };
+    if let Some(addr) = server {
+        match join_game(addr, &args, &app_state) {
+            Ok(client) => {
+                net = Some(NetGame::new(client));
+                app_state.transition_to(GameState::Network);
+            }
+            Err(e) => eprintln!("Could not open a socket: {}", e),
+        }
+    }
 
     loop {
         let dt = get_frame_time();
+        // Every pad is polled once per frame; menus take input from all devices at once
+        let pad_frames: Vec<InputFrame> = gamepads.iter_mut().map(|pad| {
+            pad.mapper.deadzone = settings.gamepad_deadzone;
+            pad.poll()
+        }).collect();
+        let input = pad_frames.iter().cloned().fold(keyboard.poll(), InputFrame::merge);
         animation_time += dt;
 
         match app_state.game_state {
             GameState::Menu => {
-                handle_menu_input(&mut app_state);
-                render::draw_ui::draw_menu(&theme, app_state.menu_selection, animation_time);
+                handle_menu_input(&input, &mut app_state, &mut session);
+                let labels: Vec<&str> = app_state.menu_items().iter().map(|i| i.label()).collect();
+                render::draw_ui::draw_menu(&theme, &labels, app_state.menu_selection, animation_time);
             }
             GameState::ModeSelect => {
-                handle_mode_select_input(&mut app_state, &mut session, &settings, &mut rng);
+                handle_mode_select_input(&input, &mut app_state, &settings);
                 render::draw_ui::draw_mode_select(&theme, app_state.mode_selection, animation_time);
             }
+            GameState::Daily => {
+                let challenge = DailyChallenge::for_date(Date::today_utc());
+                handle_daily_input(&input, &mut app_state, &mut session, &challenge, &mut rng);
+                render::draw_ui::draw_daily(&theme, &challenge, &daily_history, app_state.daily_selection, animation_time);
+            }
+            GameState::Lobby => {
+                handle_lobby_input(&input, &mut app_state, &mut session, &mut rng);
+                render::draw_ui::draw_lobby(&theme, &app_state.match_setup, app_state.lobby_selection, animation_time);
+            }
             GameState::Playing => {
                 if let Some(ref mut sess) = session {
-                    update_game(sess, &mut app_state, &settings, dt, &mut rng);
-                    render_game(sess, &theme, &settings);
+                    let frames = player_frames(&sess.bindings, &input, &pad_frames);
+                    update_game(&input, &frames, sess, &mut app_state, &settings, &mut profile, &mut daily_history, &mut high_scores, dt);
+                    render_session(sess, &theme, &settings);
+                    update_toasts(sess, &theme, dt);
                 }
             }
             GameState::Pause => {
                 if let Some(ref sess) = session {
-                    render_game(sess, &theme, &settings);
+                    render_session(sess, &theme, &settings);
                 }
                 render::draw_ui::draw_pause_overlay(&theme, app_state.pause_selection, animation_time);
-                handle_pause_input(&mut app_state, &mut session, &settings, &mut rng);
+                handle_pause_input(&input, &mut app_state, &mut session, &mut rng);
             }
             GameState::Results => {
                 if let Some(ref mut sess) = session {
                     sess.results_time += dt;
-                    render_game(sess, &theme, &settings);
-                    let pr = sess.leaderboard.get_player_rank().unwrap_or(sess.holes.len());
-                    let ps = sess.holes[sess.player_idx].radius;
-                    let cc = sess.world.get_consumption_percentage();
-                    render::draw_ui::draw_results(&theme, sess.mode_rules.mode, pr, ps, sess.holes.len(), cc, app_state.results_selection, sess.results_time);
+                    render_session(sess, &theme, &settings);
+                    let sim = &sess.sim;
+                    let pr = sim.rank_of(sim.player_idx);
+                    let ps = sim.player().radius;
+                    // Split screen ranks every human instead of the first player's line
+                    let standings = if sim.human_count > 1 { sim.standings() } else { Vec::new() };
+                    render::draw_ui::draw_results(&theme, &sim.result, sim.player_elimination(), pr, ps, sim.holes.len(), &standings, sess.daily_score, sess.xp_gain.as_ref(), &RESULTS_OPTIONS, app_state.results_selection, sess.results_time);
+                    // Unlocks from the end of the round show over the results
+                    update_toasts(sess, &theme, dt);
+                }
+                handle_results_input(&input, &mut app_state, &mut session, &mut viewer, &mut rng);
+            }
+            GameState::Replay => {
+                if let Some(ref mut v) = viewer {
+                    update_replay_viewer(v, &settings, dt);
+                    let sim = &v.player.sim;
+                    render_game(sim, &v.vfx, &v.camera, v.follow_idx, &theme, &settings);
+                    let followed = if v.free_camera { None } else { Some(sim.holes[v.follow_idx].name.as_str()) };
+                    render::draw_ui::draw_replay_overlay(&theme, v.player.tick(), v.player.total_ticks(), TICK_RATE, v.speed(), v.paused, followed, animation_time);
+                }
+                if input.pressed(MenuAction::Back) {
+                    viewer = None;
+                    // Back to the results of the round we came from, if any
+                    app_state.transition_to(if session.is_some() { GameState::Results } else { GameState::Menu });
+                }
+            }
+            GameState::Settings => {
+                handle_settings_input(&input, &mut app_state, &mut settings, &mut theme);
+                render::draw_ui::draw_settings(&theme, &settings, app_state.settings_selection, animation_time);
+            }
+            GameState::Profile => {
+                handle_profile_input(&input, &mut app_state);
+                render::draw_ui::draw_profile(&theme, &profile, &app_state.player_name, app_state.profile_selection, animation_time);
+            }
+            GameState::NameEntry => {
+                handle_name_entry_input(&input, &mut app_state, &mut settings);
+                let first_run = settings.player_name.is_empty();
+                render::draw_ui::draw_name_entry(&theme, &app_state.name_input, app_state.keyboard.as_ref(), first_run, animation_time);
+            }
+            GameState::HighScores => {
+                handle_high_scores_input(&input, &mut app_state, &high_scores);
+                render::draw_ui::draw_high_scores(&theme, &high_scores, &app_state.highscore_filter, app_state.highscores_selection, animation_time);
+            }
+            GameState::Customize => {
+                handle_customize_input(&input, &mut app_state, &mut settings, &profile);
+                render::draw_ui::draw_customize(&theme, app_state.customize_skin, &profile, &app_state.player_name, app_state.customize_selection, animation_time);
+            }
+            GameState::Network => {
+                if let Some(ref mut game) = net {
+                    update_network(&input, game, &settings, dt);
+                    render_network(game, &theme, &settings, animation_time);
+                }
+                // Esc leaves any time, Enter once the round is over or the connection is gone
+                let finished = net.as_ref().is_none_or(|g| match &g.client.status {
+                    ClientStatus::Playing => g.client.sim.as_ref().is_some_and(|s| s.game_over),
+                    status => matches!(status, ClientStatus::Refused(_) | ClientStatus::TimedOut),
+                });
+                if input.pressed(MenuAction::Back) || (finished && input.pressed(MenuAction::Confirm)) {
+                    if let Some(ref mut game) = net {
+                        game.client.leave();
+                    }
+                    net = None;
+                    app_state.transition_to(GameState::Menu);
                 }
-                handle_results_input(&mut app_state, &mut session, &settings, &mut rng);
             }
         }
 
@@ -183,199 +370,784 @@ async fn main() {
     }
 }
 
-fn handle_menu_input(app_state: &mut AppState) {
-    if is_key_pressed(KeyCode::Up) { app_state.menu_selection = app_state.menu_selection.saturating_sub(1); }
-    if is_key_pressed(KeyCode::Down) { app_state.menu_selection = (app_state.menu_selection + 1).min(2); }
-    if is_key_pressed(KeyCode::Enter) {
-        match app_state.menu_selection {
-            0 => app_state.transition_to(GameState::ModeSelect),
-            1 => {} // Settings TODO
-            2 => std::process::exit(0),
-            _ => {}
+fn handle_menu_input(input: &InputFrame, app_state: &mut AppState, session: &mut Option<GameSession>) {
+    let items = app_state.menu_items();
+    if input.pressed(MenuAction::Up) { app_state.menu_selection = app_state.menu_selection.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.menu_selection = (app_state.menu_selection + 1).min(items.len() - 1); }
+    if input.pressed(MenuAction::Confirm) {
+        match items.get(app_state.menu_selection) {
+            Some(MenuItem::Continue) => continue_saved_session(app_state, session),
+            Some(MenuItem::Play) => app_state.transition_to(GameState::ModeSelect),
+            Some(MenuItem::Profile) => app_state.transition_to(GameState::Profile),
+            Some(MenuItem::HighScores) => app_state.transition_to(GameState::HighScores),
+            Some(MenuItem::Customize) => {
+                app_state.customize_skin = app_state.player_skin;
+                app_state.transition_to(GameState::Customize);
+            }
+            Some(MenuItem::Settings) => app_state.transition_to(GameState::Settings),
+            Some(MenuItem::Quit) => std::process::exit(0),
+            None => {}
+        }
+    }
+}
+
+/// Edit settings in place; changes apply immediately and are written to disk on leaving
+fn handle_settings_input(input: &InputFrame, app_state: &mut AppState, settings: &mut Settings, theme: &mut Theme) {
+    // Setting rows, then "RESET TO DEFAULTS" and "BACK"
+    let rows = SettingId::ALL.len() + 2;
+    let sel = app_state.settings_selection;
+    if input.pressed(MenuAction::Up) { app_state.settings_selection = sel.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.settings_selection = (sel + 1).min(rows - 1); }
+
+    if let Some(&id) = SettingId::ALL.get(sel) {
+        let steps = input.horizontal();
+        if steps != 0 || input.pressed(MenuAction::Confirm) {
+            settings.adjust(id, if steps == 0 { 1 } else { steps });
+        }
+    } else if input.pressed(MenuAction::Confirm) {
+        if sel == SettingId::ALL.len() {
+            // The skin and name are picked on their own screens and survive a reset
+            *settings = Settings { skin: settings.skin, player_name: std::mem::take(&mut settings.player_name), ..Settings::default() };
+        } else {
+            leave_settings(app_state, settings);
+        }
+    }
+    theme.set_palette_index(settings.theme_index);
+
+    if input.pressed(MenuAction::Back) { leave_settings(app_state, settings); }
+}
+
+fn leave_settings(app_state: &mut AppState, settings: &Settings) {
+    save_settings(settings);
+    app_state.transition_to(GameState::Menu);
+}
+
+fn save_settings(settings: &Settings) {
+    let path = settings_path();
+    if let Err(e) = settings.save(&path) {
+        eprintln!("Could not save settings {}: {}", path.display(), e);
+    }
+}
+
+fn handle_profile_input(input: &InputFrame, app_state: &mut AppState) {
+    // "EDIT NAME", "BACK"
+    if input.pressed(MenuAction::Left) { app_state.profile_selection = 0; }
+    if input.pressed(MenuAction::Right) { app_state.profile_selection = 1; }
+    if input.pressed(MenuAction::Back) || (app_state.profile_selection == 1 && input.pressed(MenuAction::Confirm)) {
+        app_state.transition_to(GameState::Menu);
+    } else if input.pressed(MenuAction::Confirm) {
+        app_state.edit_name(GameState::Profile);
+    }
+}
+
+/// Type the player name, with the on-screen keyboard (TAB / Y) as a fallback; confirmed names are saved to the settings
+fn handle_name_entry_input(input: &InputFrame, app_state: &mut AppState, settings: &mut Settings) {
+    let field = &mut app_state.name_input;
+    // Typed characters always go in, the on-screen keyboard is only an alternative
+    for &c in &input.text {
+        field.insert(c);
+    }
+
+    let mut confirm = false;
+    if let Some(ref mut osk) = app_state.keyboard {
+        if input.pressed(MenuAction::Left) { osk.move_by(-1, 0); }
+        if input.pressed(MenuAction::Right) { osk.move_by(1, 0); }
+        if input.pressed(MenuAction::Up) { osk.move_by(0, -1); }
+        if input.pressed(MenuAction::Down) { osk.move_by(0, 1); }
+        if input.pressed(MenuAction::Erase) { field.backspace(); }
+        if input.pressed(MenuAction::Confirm) { confirm = osk.press(field); }
+        if input.pressed(MenuAction::Back) || input.pressed(MenuAction::Keyboard) {
+            app_state.keyboard = None;
+            return;
+        }
+    } else {
+        if input.pressed(MenuAction::Erase) { field.backspace(); }
+        if is_key_pressed(KeyCode::Delete) { field.delete(); }
+        if input.pressed(MenuAction::Left) { field.move_caret(-1); }
+        if input.pressed(MenuAction::Right) { field.move_caret(1); }
+        if is_key_pressed(KeyCode::Home) { field.home(); }
+        if is_key_pressed(KeyCode::End) { field.end(); }
+        if input.pressed(MenuAction::Keyboard) { app_state.keyboard = Some(OnScreenKeyboard::default()); }
+        confirm = input.pressed(MenuAction::Confirm);
+        if input.pressed(MenuAction::Back) {
+            // Leaving the first-run prompt keeps the default name, so it is not asked again
+            if settings.player_name.is_empty() {
+                settings.player_name = DEFAULT_NAME.to_string();
+                save_settings(settings);
+            }
+            app_state.transition_to(app_state.name_entry_return);
+            return;
+        }
+    }
+
+    if let Some(name) = confirm.then(|| field.value()).flatten() {
+        app_state.player_name = name.clone();
+        settings.player_name = name;
+        save_settings(settings);
+        app_state.transition_to(app_state.name_entry_return);
+    }
+}
+
+/// Browse the skin catalog; unlocked picks are worn right away and written to disk on leaving
+fn handle_customize_input(input: &InputFrame, app_state: &mut AppState, settings: &mut Settings, profile: &PlayerProfile) {
+    // Pattern and border rows, then "BACK"
+    let sel = app_state.customize_selection;
+    if input.pressed(MenuAction::Up) { app_state.customize_selection = sel.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.customize_selection = (sel + 1).min(2); }
+
+    let steps = input.horizontal();
+    let cycle = |value: u8, count: usize| (value as i32 + steps).rem_euclid(count as i32) as u8;
+    let skin = &mut app_state.customize_skin;
+    match sel {
+        0 if steps != 0 => {
+            skin.pattern = cycle(skin.pattern, skins::PATTERNS.len());
+            if skin.pattern_def().is_some_and(|d| d.unlock.is_met(profile)) { settings.skin.pattern = skin.pattern; }
         }
+        1 if steps != 0 => {
+            skin.border = cycle(skin.border, skins::BORDERS.len());
+            if skin.border_def().is_some_and(|d| d.unlock.is_met(profile)) { settings.skin.border = skin.border; }
+        }
+        _ => {}
+    }
+
+    if input.pressed(MenuAction::Back) || (sel == 2 && input.pressed(MenuAction::Confirm)) {
+        app_state.player_skin = settings.skin.unlocked_for(profile);
+        leave_settings(app_state, settings);
     }
 }
 
-fn handle_mode_select_input(app_state: &mut AppState, session: &mut Option<GameSession>, _settings: &Settings, rng: &mut impl Rng) {
-    if is_key_pressed(KeyCode::Left) { app_state.mode_selection = app_state.mode_selection.saturating_sub(1); }
-    if is_key_pressed(KeyCode::Right) { app_state.mode_selection = (app_state.mode_selection + 1).min(2); }
-    if is_key_pressed(KeyCode::Escape) { app_state.transition_to(GameState::Menu); }
-    if is_key_pressed(KeyCode::Enter) {
+fn handle_high_scores_input(input: &InputFrame, app_state: &mut AppState, high_scores: &HighScores) {
+    // Filter rows, then "BACK"
+    let rows = HighScoreField::ALL.len() + 1;
+    let sel = app_state.highscores_selection;
+    if input.pressed(MenuAction::Up) { app_state.highscores_selection = sel.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.highscores_selection = (sel + 1).min(rows - 1); }
+
+    if let Some(field) = HighScoreField::ALL.get(sel) {
+        if input.pressed(MenuAction::Right) { field.adjust(&mut app_state.highscore_filter, high_scores, 1); }
+        if input.pressed(MenuAction::Left) { field.adjust(&mut app_state.highscore_filter, high_scores, -1); }
+    }
+
+    if input.pressed(MenuAction::Back) || (sel == rows - 1 && input.pressed(MenuAction::Confirm)) {
+        app_state.transition_to(GameState::Menu);
+    }
+}
+
+/// Load the saved round and jump back into it; the save slot is consumed
+fn continue_saved_session(app_state: &mut AppState, session: &mut Option<GameSession>) {
+    let path = savegame::save_path();
+    // Either resumed or put aside, the slot is not offered again
+    app_state.has_saved_session = false;
+    match SavedSession::load(&path) {
+        Ok(saved) => {
+            let _ = std::fs::remove_file(&path);
+            let daily = saved.daily;
+            let mut sess = GameSession::from_saved(saved);
+            sess.sim.clock.resume();
+            // "Restart" replays the saved round's rules with a fresh seed
+            app_state.match_setup.rules = sess.sim.mode_rules.clone();
+            app_state.match_setup.seed_text.clear();
+            app_state.match_setup.daily = daily;
+            app_state.match_setup.players = sess.sim.human_count;
+            app_state.match_setup.bindings = std::array::from_fn(|slot| sess.bindings.get(slot).copied().unwrap_or(Binding::default_for(slot)));
+            app_state.start_game(sess.sim.mode_rules.mode);
+            *session = Some(sess);
+        }
+        Err(e) => {
+            eprintln!("Could not load saved session {}: {}", path.display(), e);
+            // Kept aside rather than lost, an older build may still read it
+            let _ = std::fs::rename(&path, path.with_extension("sav.bak"));
+            app_state.transition_to(GameState::Menu);
+        }
+    }
+}
+
+fn handle_mode_select_input(input: &InputFrame, app_state: &mut AppState, settings: &Settings) {
+    if input.pressed(MenuAction::Left) { app_state.mode_selection = app_state.mode_selection.saturating_sub(1); }
+    if input.pressed(MenuAction::Right) { app_state.mode_selection = (app_state.mode_selection + 1).min(3); }
+    if input.pressed(MenuAction::Back) { app_state.transition_to(GameState::Menu); }
+    if input.pressed(MenuAction::Confirm) {
         let mode = match app_state.mode_selection {
             0 => GameMode::Classic,
             1 => GameMode::Battle,
-            _ => GameMode::Solo,
+            2 => GameMode::Solo,
+            _ => { app_state.transition_to(GameState::Daily); return; }
         };
-        *session = Some(GameSession::new(mode, &app_state.player_name, rng.gen()));
-        if let Some(ref mut s) = session { s.clock.start(); }
-        app_state.start_game(mode);
+        app_state.selected_mode = mode;
+        // Who is playing and on which devices carries over to the next mode
+        let previous = std::mem::replace(&mut app_state.match_setup, MatchSetup::new(mode, settings));
+        if previous.daily.is_none() {
+            app_state.match_setup.players = previous.players;
+            app_state.match_setup.bindings = previous.bindings;
+        }
+        app_state.transition_to(GameState::Lobby);
     }
 }
 
-fn handle_pause_input(app_state: &mut AppState, session: &mut Option<GameSession>, _settings: &Settings, rng: &mut impl Rng) {
-    if is_key_pressed(KeyCode::Up) { app_state.pause_selection = app_state.pause_selection.saturating_sub(1); }
-    if is_key_pressed(KeyCode::Down) { app_state.pause_selection = (app_state.pause_selection + 1).min(2); }
-    if is_key_pressed(KeyCode::Escape) {
-        if let Some(ref mut s) = session { s.clock.resume(); }
+fn handle_lobby_input(input: &InputFrame, app_state: &mut AppState, session: &mut Option<GameSession>, rng: &mut impl Rng) {
+    // Field rows, then "START" and "BACK"
+    let fields = LobbyField::rows(&app_state.match_setup);
+    let rows = fields.len() + 2;
+    let sel = app_state.lobby_selection;
+    if input.pressed(MenuAction::Up) { app_state.lobby_selection = sel.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.lobby_selection = (sel + 1).min(rows - 1); }
+    if input.pressed(MenuAction::Back) { app_state.transition_to(GameState::ModeSelect); return; }
+
+    if let Some(&field) = fields.get(sel) {
+        if field == LobbyField::Seed {
+            for &c in &input.text {
+                app_state.match_setup.push_seed_char(c);
+            }
+            if input.pressed(MenuAction::Erase) { app_state.match_setup.pop_seed_char(); }
+        }
+        if input.pressed(MenuAction::Right) { field.adjust(&mut app_state.match_setup, 1); }
+        if input.pressed(MenuAction::Left) { field.adjust(&mut app_state.match_setup, -1); }
+    }
+
+    if input.pressed(MenuAction::Confirm) {
+        if sel == fields.len() + 1 {
+            app_state.transition_to(GameState::ModeSelect);
+        } else {
+            start_match(app_state, session, rng);
+        }
+    }
+}
+
+fn handle_daily_input(input: &InputFrame, app_state: &mut AppState, session: &mut Option<GameSession>, challenge: &DailyChallenge, rng: &mut impl Rng) {
+    if input.pressed(MenuAction::Up) { app_state.daily_selection = 0; }
+    if input.pressed(MenuAction::Down) { app_state.daily_selection = 1; }
+    if input.pressed(MenuAction::Back) || (app_state.daily_selection == 1 && input.pressed(MenuAction::Confirm)) {
+        app_state.transition_to(GameState::ModeSelect);
+    } else if input.pressed(MenuAction::Confirm) {
+        app_state.selected_mode = challenge.mode;
+        app_state.match_setup = MatchSetup::daily(challenge);
+        start_match(app_state, session, rng);
+    }
+}
+
+/// Start a round from the current match setup; a typed seed is reused, otherwise a new one is rolled
+fn start_match(app_state: &mut AppState, session: &mut Option<GameSession>, rng: &mut impl Rng) {
+    let setup = &app_state.match_setup;
+    // The first player is the profile's; the others join under their slot name
+    let players: Vec<PlayerSlot> = (0..setup.players).map(|slot| match slot {
+        0 => PlayerSlot::new(&app_state.player_name, app_state.player_skin),
+        _ => PlayerSlot::new(&format!("P{}", slot + 1), HoleSkin::default()),
+    }).collect();
+    let mut sess = GameSession::new(&setup.rules, &players, setup.active_bindings(), setup.seed(rng.gen()));
+    sess.sim.start();
+    *session = Some(sess);
+    app_state.start_game(app_state.match_setup.rules.mode);
+}
+
+fn handle_pause_input(input: &InputFrame, app_state: &mut AppState, session: &mut Option<GameSession>, rng: &mut impl Rng) {
+    if input.pressed(MenuAction::Up) { app_state.pause_selection = app_state.pause_selection.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.pause_selection = (app_state.pause_selection + 1).min(3); }
+    if input.pause || input.pressed(MenuAction::Back) {
+        if let Some(ref mut s) = session { s.sim.clock.resume(); }
         app_state.transition_to(GameState::Playing);
     }
-    if is_key_pressed(KeyCode::Enter) {
+    if input.pressed(MenuAction::Confirm) {
         match app_state.pause_selection {
-            0 => { if let Some(ref mut s) = session { s.clock.resume(); } app_state.transition_to(GameState::Playing); }
-            1 => { *session = Some(GameSession::new(app_state.selected_mode, &app_state.player_name, rng.gen())); if let Some(ref mut s) = session { s.clock.start(); } app_state.transition_to(GameState::Playing); }
-            2 => { *session = None; app_state.transition_to(GameState::Menu); }
+            0 => { if let Some(ref mut s) = session { s.sim.clock.resume(); } app_state.transition_to(GameState::Playing); }
+            1 => start_match(app_state, session, rng),
+            2 => {
+                if let Some(s) = session.take() {
+                    app_state.has_saved_session = s.save(app_state.match_setup.daily);
+                }
+                app_state.transition_to(GameState::Menu);
+            }
+            3 => { *session = None; app_state.transition_to(GameState::Menu); }
             _ => {}
         }
     }
 }
 
-fn handle_results_input(app_state: &mut AppState, session: &mut Option<GameSession>, _settings: &Settings, rng: &mut impl Rng) {
-    if is_key_pressed(KeyCode::Up) { app_state.results_selection = app_state.results_selection.saturating_sub(1); }
-    if is_key_pressed(KeyCode::Down) { app_state.results_selection = (app_state.results_selection + 1).min(2); }
-    if is_key_pressed(KeyCode::Enter) {
+fn handle_results_input(input: &InputFrame, app_state: &mut AppState, session: &mut Option<GameSession>, viewer: &mut Option<ReplayViewer>, rng: &mut impl Rng) {
+    if input.pressed(MenuAction::Up) { app_state.results_selection = app_state.results_selection.saturating_sub(1); }
+    if input.pressed(MenuAction::Down) { app_state.results_selection = (app_state.results_selection + 1).min(RESULTS_OPTIONS.len() - 1); }
+    if input.pressed(MenuAction::Confirm) {
         match app_state.results_selection {
-            0 => { *session = Some(GameSession::new(app_state.selected_mode, &app_state.player_name, rng.gen())); if let Some(ref mut s) = session { s.clock.start(); } app_state.transition_to(GameState::Playing); }
-            1 => { *session = None; app_state.transition_to(GameState::ModeSelect); }
-            2 => { *session = None; app_state.transition_to(GameState::Menu); }
+            0 => start_match(app_state, session, rng),
+            1 => {
+                if let Some(ref s) = session {
+                    *viewer = Some(ReplayViewer::new(s.replay.clone()));
+                    app_state.transition_to(GameState::Replay);
+                }
+            }
+            2 => { *session = None; app_state.transition_to(GameState::ModeSelect); }
+            3 => { *session = None; app_state.transition_to(GameState::Menu); }
             _ => {}
         }
     }
 }
 
-fn update_game(sess: &mut GameSession, app_state: &mut AppState, settings: &Settings, dt: f32, rng: &mut impl Rng) {
-    if sess.game_over { return; }
-    
+/// Keyboard as an input source: WASD/arrows move, Shift dashes, arrows/Enter/Esc drive menus
+struct KeyboardInput;
+
+/// Movement and dash keys of a split-screen player sharing the keyboard
+struct KeyboardHalf {
+    up: KeyCode,
+    down: KeyCode,
+    left: KeyCode,
+    right: KeyCode,
+    dash: KeyCode,
+}
+
+const WASD_KEYS: KeyboardHalf = KeyboardHalf { up: KeyCode::W, down: KeyCode::S, left: KeyCode::A, right: KeyCode::D, dash: KeyCode::LeftShift };
+const ARROW_KEYS: KeyboardHalf = KeyboardHalf { up: KeyCode::Up, down: KeyCode::Down, left: KeyCode::Left, right: KeyCode::Right, dash: KeyCode::RightShift };
+
+impl KeyboardHalf {
+    /// Movement and dash only, menus keep reading the whole keyboard
+    fn frame(&self) -> InputFrame {
+        let x = is_key_down(self.right) as i32 - is_key_down(self.left) as i32;
+        let y = is_key_down(self.down) as i32 - is_key_down(self.up) as i32;
+        InputFrame {
+            movement: vec2(x as f32, y as f32).normalize_or_zero(),
+            dash: is_key_pressed(self.dash),
+            ..InputFrame::default()
+        }
+    }
+}
+
+/// Each human's input for the frame: a lone player uses every device, split-screen players their own
+fn player_frames(bindings: &[Binding], all: &InputFrame, pads: &[InputFrame]) -> Vec<InputFrame> {
+    if bindings.len() <= 1 {
+        return vec![all.clone()];
+    }
+    bindings.iter().map(|binding| match binding {
+        Binding::Wasd => WASD_KEYS.frame(),
+        Binding::Arrows => ARROW_KEYS.frame(),
+        Binding::Gamepad(i) => pads.get(*i as usize).cloned().unwrap_or_default(),
+    }).collect()
+}
+
+/// Empty macroquad's character queue, which nothing else clears and which pops the newest
+/// character first; draining it every frame keeps keys pressed in play out of text fields
+fn typed_chars() -> Vec<char> {
+    let mut chars: Vec<char> = std::iter::from_fn(get_char_pressed).collect();
+    chars.reverse();
+    chars
+}
+
+impl InputSource for KeyboardInput {
+    fn poll(&mut self) -> InputFrame {
+        let mut movement = Vec2::ZERO;
+        if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) { movement.y -= 1.0; }
+        if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) { movement.y += 1.0; }
+        if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) { movement.x -= 1.0; }
+        if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) { movement.x += 1.0; }
+        let keys = [
+            (KeyCode::Up, MenuAction::Up),
+            (KeyCode::Down, MenuAction::Down),
+            (KeyCode::Left, MenuAction::Left),
+            (KeyCode::Right, MenuAction::Right),
+            (KeyCode::Enter, MenuAction::Confirm),
+            (KeyCode::Escape, MenuAction::Back),
+            (KeyCode::Backspace, MenuAction::Erase),
+            (KeyCode::Tab, MenuAction::Keyboard),
+        ];
+        InputFrame {
+            movement: movement.normalize_or_zero(),
+            dash: is_key_pressed(KeyCode::LeftShift) || is_key_pressed(KeyCode::RightShift),
+            pause: is_key_pressed(KeyCode::Escape),
+            menu: keys.iter().filter(|(key, _)| is_key_pressed(*key)).map(|&(_, action)| action).collect(),
+            text: typed_chars(),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_game(input: &InputFrame, frames: &[InputFrame], sess: &mut GameSession, app_state: &mut AppState, settings: &Settings, profile: &mut PlayerProfile, daily_history: &mut DailyHistory, high_scores: &mut HighScores, dt: f32) {
+    if sess.sim.game_over { return; }
+
     // Pause check
-    if is_key_pressed(KeyCode::Escape) {
-        sess.clock.pause();
+    if input.pause {
+        sess.sim.clock.pause();
         app_state.transition_to(GameState::Pause);
         return;
     }
 
-    // Update clock
-    let time_up = sess.clock.update(dt);
-    
-    // Player input
-    let player = &mut sess.holes[sess.player_idx];
-    if player.is_alive {
-        let mut vel = Vec2::ZERO;
-        if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) { vel.y -= 1.0; }
-        if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) { vel.y += 1.0; }
-        if is_key_down(KeyCode::A) || is_key_down(KeyCode::Left) { vel.x -= 1.0; }
-        if is_key_down(KeyCode::D) || is_key_down(KeyCode::Right) { vel.x += 1.0; }
-        player.set_velocity(vel);
-        
-        if is_key_pressed(KeyCode::LeftShift) || is_key_pressed(KeyCode::RightShift) {
-            player.try_dash(settings.dash_cooldown, settings.dash_duration);
-        }
+    // Advance the simulation in fixed ticks, recording every human's input
+    let live: Vec<HoleInput> = frames.iter().map(InputFrame::hole_input).collect();
+    for (pending, input) in sess.pending_dash.iter_mut().zip(&live) {
+        *pending |= input.dash;
+    }
+    for _ in 0..sess.timestep.advance(dt) {
+        let tick_inputs: Vec<HoleInput> = live.iter().zip(&mut sess.pending_dash)
+            .map(|(input, pending)| HoleInput { dash: std::mem::take(pending), ..*input })
+            .collect();
+        sess.replay.record(&tick_inputs);
+        sess.sim.step(&tick_inputs, TICK_DT);
+        if sess.sim.game_over { break; }
     }
 
-    // Update bot AI
-    for i in 1..sess.holes.len() {
-        let hole = sess.holes[i].clone();
-        if hole.is_alive {
-            let vel = sess.bot_controllers[i].update(&hole, &sess.holes, &sess.world.objects, &sess.spatial, dt, rng);
-            sess.holes[i].set_velocity(vel);
+    // Achievements from this frame's gameplay events
+    let events: Vec<_> = sess.sim.events.drain(..).collect();
+    let mut unlocked = false;
+    for achievement in sess.achievements.process(&events, &sess.sim) {
+        if profile.unlock_achievement(achievement) {
+            sess.toasts.push(Toast { achievement, age: 0.0 });
+            unlocked = true;
         }
     }
 
-    // Update all holes
-    for hole in &mut sess.holes {
-        hole.update(dt, sess.world.width, sess.world.height, settings.move_speed);
+    // Update VFX
+    sess.vfx.configure(settings.particle_density, settings.screen_shake_intensity);
+    for effect in sess.sim.effects.drain(..) {
+        sess.vfx.spawn(effect);
     }
+    sess.vfx.update(dt);
 
-    // Rebuild spatial grid
-    sess.spatial.build(&sess.world.objects);
+    // Update cameras, each in its share of the screen
+    let split = sess.cameras.len() > 1;
+    let views = viewport::split_viewports(sess.cameras.len(), screen_width(), screen_height());
+    for ((camera, view), hole) in sess.cameras.iter_mut().zip(views).zip(sess.sim.humans()) {
+        camera.viewport = split.then_some(view);
+        camera.follow(hole.x, hole.y, hole.radius, dt, settings.camera_smoothing);
+    }
 
-    // Process swallowing for each hole
-    for i in 0..sess.holes.len() {
-        let hole = &mut sess.holes[i];
-        if hole.is_alive {
-            swallow::process_swallow(hole, &mut sess.world.objects, &sess.spatial, &mut sess.vfx);
+    if sess.sim.game_over {
+        save_replay(&sess.replay);
+        sess.xp_gain = Some(profile.record_match(&MatchSummary::from_simulation(&sess.sim)));
+        save_profile(profile);
+        // Every human's result goes in the table, the profile only tracks the first player
+        let key = TableKey::for_simulation(&sess.sim);
+        let mut made_table = false;
+        for slot in 0..sess.sim.human_count {
+            let entry = HighScoreEntry::for_human(&sess.sim, slot, Date::today_utc());
+            made_table |= high_scores.record(key, entry).is_some();
         }
+        if made_table {
+            let path = highscores_path();
+            if let Err(e) = high_scores.save(&path) {
+                eprintln!("Could not save high scores {}: {}", path.display(), e);
+            }
+        }
+        if let Some(date) = app_state.match_setup.daily {
+            let score = DailyChallenge::for_date(date).score(sess.sim.player());
+            sess.daily_score = Some((score, daily_history.record(date, score)));
+            let path = daily_history_path();
+            if let Err(e) = daily_history.save(&path) {
+                eprintln!("Could not save daily history {}: {}", path.display(), e);
+            }
+        }
+        app_state.transition_to(GameState::Results);
+    } else if unlocked {
+        save_profile(profile);
+    }
+}
+
+/// Value after a command line flag
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == name).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+/// Start a server in the background: `--host [PORT] [--players N] [--mode NAME]`; returns its address
+fn host_game(args: &[String], settings: &Settings, rng: &mut impl Rng) -> Option<std::net::SocketAddr> {
+    let port = args.first().and_then(|a| a.parse().ok()).unwrap_or(DEFAULT_PORT);
+    let mode = flag(args, "--mode").and_then(GameMode::from_name).unwrap_or(GameMode::Classic);
+    let mut config = ServerConfig::new(ModeRules::new(mode, settings), rng.gen());
+    config.min_players = flag(args, "--players").and_then(|n| n.parse().ok()).unwrap_or(DEFAULT_HOST_PLAYERS);
+
+    let server = match Server::bind(std::net::SocketAddr::from(([0, 0, 0, 0], port)), config) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Could not host on port {}: {}", port, e);
+            return None;
+        }
+    };
+    if let Err(e) = std::thread::Builder::new().name("server".to_string()).spawn(move || server.run(|_| {})) {
+        eprintln!("Could not start the server thread: {}", e);
+        return None;
+    }
+    Some(std::net::SocketAddr::from(([127, 0, 0, 1], port)))
+}
+
+/// Client for a server; `--latency`/`--jitter`/`--buffer` in milliseconds and `--loss` in percent
+/// simulate a worse network and tune the interpolation buffer
+fn join_game(addr: std::net::SocketAddr, args: &[String], app_state: &AppState) -> std::io::Result<NetClient> {
+    let number = |name: &str| flag(args, name).and_then(|v| v.parse::<f32>().ok());
+    let link = LinkConditions {
+        latency: number("--latency").unwrap_or(0.0) / 1000.0,
+        jitter: number("--jitter").unwrap_or(0.0) / 1000.0,
+        loss: number("--loss").unwrap_or(0.0) / 100.0,
+    };
+    let mut client = NetClient::connect(addr, net_name(app_state), app_state.player_skin, link)?;
+    if let Some(ms) = number("--buffer") {
+        client.interpolation_delay = ms / 1000.0;
     }
+    Ok(client)
+}
+
+/// `HOST` or `HOST:PORT`, resolved
+fn server_address(arg: &str) -> Option<std::net::SocketAddr> {
+    use std::net::ToSocketAddrs;
+    let with_port = if arg.contains(':') { arg.to_string() } else { format!("{}:{}", arg, DEFAULT_PORT) };
+    with_port.to_socket_addrs().ok()?.next()
+}
 
-    // Update falling objects
-    for i in 0..sess.holes.len() {
-        let hole = &mut sess.holes[i];
-        swallow::update_falling_objects(hole, &mut sess.world.objects, dt);
+/// Name shown to the other players; the first run may not have asked for one yet
+fn net_name(app_state: &AppState) -> &str {
+    if app_state.player_name.is_empty() { DEFAULT_NAME } else { &app_state.player_name }
+}
+
+/// Talk to the server and follow the local hole; network rounds leave the profile, scores and replays alone
+fn update_network(input: &InputFrame, game: &mut NetGame, settings: &Settings, dt: f32) {
+    // Input first, so the ticks of this frame already predict with it
+    game.client.set_input(input.hole_input());
+    for event in game.client.update(dt) {
+        match event {
+            ClientEvent::RoundStarted => {
+                game.camera = Camera::new();
+                game.vfx.clear();
+                game.results_time = 0.0;
+            }
+            ClientEvent::RoundOver => game.results_time = 0.0,
+        }
+    }
+    let Some(sim) = &mut game.client.sim else { return };
+    let hole = sim.player();
+    game.camera.follow(hole.x, hole.y, hole.radius, dt, settings.camera_smoothing);
+    if sim.game_over {
+        game.results_time += dt;
     }
+    // Captures the server reported, started locally; nothing here counts toward achievements
+    game.vfx.configure(settings.particle_density, settings.screen_shake_intensity);
+    for effect in sim.effects.drain(..) {
+        game.vfx.spawn(effect);
+    }
+    sim.events.clear();
+    game.vfx.update(dt);
+}
 
-    // Hole vs hole combat
-    swallow::process_hole_combat(&mut sess.holes, sess.player_idx, &mut sess.vfx, sess.mode_rules.mode.allows_respawn(), sess.mode_rules.respawn_time);
+fn render_network(game: &NetGame, theme: &Theme, settings: &Settings, animation_time: f32) {
+    let server = game.client.server_addr().to_string();
+    let sim = match (&game.client.status, &game.client.sim) {
+        (ClientStatus::Playing, Some(sim)) => sim,
+        (status, _) => {
+            render::draw_ui::draw_network_status(theme, status, &server, animation_time);
+            return;
+        }
+    };
+    render_game(sim, &game.vfx, &game.camera, sim.player_idx, theme, settings);
+    if sim.game_over {
+        let standings = if sim.human_count > 1 { sim.standings() } else { Vec::new() };
+        let rank = sim.rank_of(sim.player_idx);
+        render::draw_ui::draw_results(theme, &sim.result, None, rank, sim.player().radius, sim.holes.len(), &standings, None, None, &["LEAVE"], 0, game.results_time);
+    }
+}
 
-    // Respawn dead holes at new positions
-    for hole in &mut sess.holes {
-        if !hole.is_alive && hole.respawn_timer <= 0.0 && sess.mode_rules.mode.allows_respawn() {
-            let pos = sess.world.get_spawn_position(rng);
-            hole.respawn(pos.x, pos.y);
+/// Age the achievement toasts and draw the current one
+fn update_toasts(sess: &mut GameSession, theme: &Theme, dt: f32) {
+    if let Some(toast) = sess.toasts.first_mut() {
+        toast.age += dt;
+        if toast.age > TOAST_DURATION {
+            sess.toasts.remove(0);
         }
     }
+    if let Some(toast) = sess.toasts.first() {
+        render::draw_ui::draw_achievement_toast(theme, toast.achievement.name(), toast.achievement.description(), toast.age, TOAST_DURATION);
+    }
+}
 
-    // Update VFX
-    sess.vfx.update(dt);
+fn save_profile(profile: &PlayerProfile) {
+    let path = profile_path();
+    if let Err(e) = profile.save(&path) {
+        eprintln!("Could not save profile {}: {}", path.display(), e);
+    }
+}
 
-    // Update camera
-    let player = &sess.holes[sess.player_idx];
-    sess.camera.follow(player.x, player.y, player.radius, dt, settings.camera_smoothing);
+fn update_replay_viewer(v: &mut ReplayViewer, settings: &Settings, dt: f32) {
+    // Playback controls
+    if is_key_pressed(KeyCode::Space) { v.paused = !v.paused; }
+    if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) { v.speed_idx = v.speed_idx.saturating_sub(1); }
+    if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) { v.speed_idx = (v.speed_idx + 1).min(REPLAY_SPEEDS.len() - 1); }
+    if is_key_pressed(KeyCode::Left) { v.seek(v.player.tick().saturating_sub(REPLAY_SEEK_TICKS)); }
+    if is_key_pressed(KeyCode::Right) { v.seek(v.player.tick() + REPLAY_SEEK_TICKS); }
+    if is_key_pressed(KeyCode::Period) && v.paused { v.player.step(TICK_DT); }
+    if is_mouse_button_pressed(MouseButton::Left) {
+        let bar = render::draw_ui::replay_timeline_rect();
+        let (mx, my) = mouse_position();
+        if mx >= bar.x && mx <= bar.x + bar.w && (my - (bar.y + bar.h / 2.0)).abs() < 15.0 {
+            let t = (mx - bar.x) / bar.w;
+            v.seek((t * v.player.total_ticks() as f32) as u64);
+        }
+    }
 
-    // Update leaderboard
-    sess.leaderboard.update(&sess.holes);
+    // Camera target
+    if is_key_pressed(KeyCode::Tab) {
+        v.follow_idx = (v.follow_idx + 1) % v.player.sim.holes.len();
+        v.free_camera = false;
+    }
+    if is_key_pressed(KeyCode::F) { v.free_camera = !v.free_camera; }
 
-    // Check victory conditions
-    let alive_count = sess.holes.iter().filter(|h| h.is_alive).count();
-    let player_alive = sess.holes[sess.player_idx].is_alive;
-    let city_consumed = sess.world.get_consumption_percentage();
+    // Advance playback
+    let speed = if v.paused { 0.0 } else { v.speed() };
+    for _ in 0..v.timestep.advance(dt * speed) {
+        v.player.step(TICK_DT);
+    }
+    v.vfx.configure(settings.particle_density, settings.screen_shake_intensity);
+    for effect in v.player.sim.effects.drain(..) {
+        v.vfx.spawn(effect);
+    }
+    // Watching a round does not count toward achievements
+    v.player.sim.events.clear();
+    v.vfx.update(dt * speed);
 
-    if time_up || (sess.mode_rules.mode == GameMode::Battle && alive_count <= 1) || (sess.mode_rules.mode == GameMode::Solo && city_consumed >= 100.0) {
-        sess.game_over = true;
-        app_state.transition_to(GameState::Results);
+    if v.free_camera {
+        let mut pan = Vec2::ZERO;
+        if is_key_down(KeyCode::W) || is_key_down(KeyCode::Up) { pan.y -= 1.0; }
+        if is_key_down(KeyCode::S) || is_key_down(KeyCode::Down) { pan.y += 1.0; }
+        if is_key_down(KeyCode::A) { pan.x -= 1.0; }
+        if is_key_down(KeyCode::D) { pan.x += 1.0; }
+        v.camera.pan(pan.x * FREE_CAMERA_SPEED * dt, pan.y * FREE_CAMERA_SPEED * dt);
+        let (_, wheel) = mouse_wheel();
+        if is_key_down(KeyCode::Q) || wheel < 0.0 { v.camera.zoom_by(0.98); }
+        if is_key_down(KeyCode::E) || wheel > 0.0 { v.camera.zoom_by(1.02); }
+    } else {
+        let hole = &v.player.sim.holes[v.follow_idx];
+        v.camera.follow(hole.x, hole.y, hole.radius, dt, settings.camera_smoothing);
     }
+}
 
-    if !player_alive && !sess.mode_rules.mode.allows_respawn() {
-        sess.game_over = true;
-        app_state.transition_to(GameState::Results);
+/// Keep a finished round on disk so it can be watched again or attached to a bug report
+fn save_replay(replay: &Replay) {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = replay::replay_dir().join(replay::replay_file_name(now, replay.rules.mode, replay.seed));
+    if let Err(e) = replay.save(&path) {
+        eprintln!("Could not save replay {}: {}", path.display(), e);
     }
 }
 
-fn render_game(sess: &GameSession, theme: &Theme, settings: &Settings) {
+/// Draw a round from a camera; the HUD shows the stats of hole `focus_idx`
+fn render_game(sim: &Simulation, vfx: &VfxSystem, camera: &Camera, focus_idx: usize, theme: &Theme, settings: &Settings) {
+    clear_background(theme.palette.background);
+    render_view(sim, vfx, camera, focus_idx, theme, settings);
+}
+
+/// Draw every human's view of the session, split screen when there are several
+fn render_session(sess: &GameSession, theme: &Theme, settings: &Settings) {
     clear_background(theme.palette.background);
+    for (i, camera) in sess.cameras.iter().enumerate() {
+        render_view(&sess.sim, &sess.vfx, camera, i, theme, settings);
+    }
+    let views: Vec<Rect> = sess.cameras.iter().filter_map(|c| c.viewport).collect();
+    viewport::draw_dividers(&views, theme.palette.ui_bg);
+}
+
+/// Draw the world and HUD from one camera, clipped to its viewport if it has one
+fn render_view(sim: &Simulation, vfx: &VfxSystem, camera: &Camera, focus_idx: usize, theme: &Theme, settings: &Settings) {
+    if let Some(rect) = camera.viewport {
+        viewport::begin(rect);
+    }
 
     let (shake_x, shake_y) = if settings.screen_shake_intensity > 0.0 {
-        let mut vfx_copy = sess.vfx.clone();
+        let mut vfx_copy = vfx.clone();
         vfx_copy.get_shake_offset()
     } else { (0.0, 0.0) };
 
-    let cam_x = sess.camera.x + shake_x;
-    let cam_y = sess.camera.y + shake_y;
-    let zoom = sess.camera.zoom;
+    let cam_x = camera.x + shake_x;
+    let cam_y = camera.y + shake_y;
+    let zoom = camera.zoom;
 
     // Draw world
-    render::draw_world::draw_world(&sess.world, theme, cam_x, cam_y, zoom);
-    render::draw_world::draw_world_bounds(&sess.world, theme, cam_x, cam_y, zoom);
+    render::draw_world::draw_world(&sim.world, theme, cam_x, cam_y, zoom);
+    render::draw_world::draw_world_bounds(&sim.world, theme, cam_x, cam_y, zoom);
+    if let Some(zone) = &sim.zone {
+        render::draw_world::draw_safe_zone(zone, theme, cam_x, cam_y, zoom);
+    }
 
     // Draw VFX (behind holes)
-    sess.vfx.draw(cam_x, cam_y, zoom);
+    vfx.draw(cam_x, cam_y, zoom);
 
     // Draw holes
-    render::draw_holes::draw_holes(&sess.holes, theme, cam_x, cam_y, zoom);
+    render::draw_holes::draw_holes(&sim.holes, theme, cam_x, cam_y, zoom);
 
     // Draw respawn indicators
-    for hole in &sess.holes {
+    for hole in &sim.holes {
         render::draw_holes::draw_respawn_indicator(hole, theme, cam_x, cam_y, zoom);
     }
 
     // Draw HUD
-    let player = &sess.holes[sess.player_idx];
+    let player = &sim.holes[focus_idx];
     render::draw_ui::draw_hud(
         theme,
-        sess.clock.remaining,
-        sess.leaderboard.top(5),
-        sess.leaderboard.get_player_rank(),
+        camera.view_size(),
+        sim.clock.remaining,
+        sim.leaderboard.top(5),
+        sim.leaderboard.get_rank(player.id),
         player.radius,
-        sess.mode_rules.mode,
-        sess.world.get_consumption_percentage(),
+        sim.mode_rules.mode,
+        sim.world.get_consumption_percentage(),
         player.dash_cooldown,
-        settings.dash_cooldown,
+        sim.mode_rules.dash_cooldown,
+        sim.zone.as_ref(),
     );
+    if camera.viewport.is_some() {
+        render::draw_ui::draw_view_label(theme, focus_idx, &player.name);
+        viewport::end();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Scripted input: returns the queued frames in order, then empty frames
+    struct MockInput {
+        frames: VecDeque<InputFrame>,
+    }
+
+    impl MockInput {
+        fn new(frames: impl IntoIterator<Item = InputFrame>) -> Self {
+            Self { frames: frames.into_iter().collect() }
+        }
+    }
+
+    impl InputSource for MockInput {
+        fn poll(&mut self) -> InputFrame {
+            self.frames.pop_front().unwrap_or_default()
+        }
+    }
+
+    fn press(action: MenuAction) -> InputFrame {
+        InputFrame { menu: vec![action], ..InputFrame::default() }
+    }
+
+    #[test]
+    fn menus_are_driven_by_any_input_source() {
+        let settings = Settings::default();
+        let mut app_state = AppState::default();
+        let mut session = None;
+        let mut input = MockInput::new([
+            press(MenuAction::Down),
+            press(MenuAction::Up),
+            press(MenuAction::Confirm),
+            press(MenuAction::Right),
+            press(MenuAction::Confirm),
+        ]);
+        let mut poll_menu = |app_state: &mut AppState| {
+            let frame = input.poll();
+            match app_state.game_state {
+                GameState::Menu => handle_menu_input(&frame, app_state, &mut session),
+                GameState::ModeSelect => handle_mode_select_input(&frame, app_state, &settings),
+                other => panic!("unexpected screen {:?}", other),
+            }
+        };
+
+        poll_menu(&mut app_state);
+        assert_eq!(app_state.menu_selection, 1);
+        poll_menu(&mut app_state);
+        poll_menu(&mut app_state);
+        assert_eq!(app_state.game_state, GameState::ModeSelect);
+        poll_menu(&mut app_state);
+        poll_menu(&mut app_state);
+        assert_eq!(app_state.game_state, GameState::Lobby);
+        assert_eq!(app_state.selected_mode, GameMode::Battle);
+        assert_eq!(app_state.match_setup.rules.mode, GameMode::Battle);
+    }
 }
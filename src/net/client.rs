@@ -0,0 +1,417 @@
+//! Network client - joins a server, sends the local player's input and mirrors the round
+//!
+//! The local hole is predicted: every tick its input is applied right away with the same
+//! `Hole::update` the server runs, and when a snapshot confirms inputs up to some sequence the
+//! hole is reset to the server's state and the inputs after it are replayed. Other holes are
+//! drawn `interpolation_delay` seconds in the past, blended between the two snapshots around
+//! that time, so they move smoothly whatever the packet timing.
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::SocketAddr;
+use crate::gameplay::simulation::{HoleInput, Simulation};
+use crate::gameplay::skins::HoleSkin;
+use crate::gameplay::swallow;
+use crate::net::protocol::{self, ClientMessage, ServerMessage, MAX_INPUTS_PER_PACKET};
+use crate::net::server::{CLIENT_TIMEOUT, RESEND_INTERVAL};
+use crate::net::snapshot::Snapshot;
+use crate::net::transport::{LinkConditions, SimulatedLink, Transport, UdpTransport};
+use crate::time::tick::{FixedTimestep, TICK_RATE};
+
+/// Received snapshots kept as baselines for the server's deltas and for interpolation
+const SNAPSHOT_HISTORY: usize = 32;
+/// How far behind the newest snapshot other holes are drawn when nothing else is configured
+pub const DEFAULT_INTERPOLATION_DELAY: f32 = 0.1;
+/// Ticks the estimated server clock may be off before it jumps instead of drifting back
+const CLOCK_RESYNC_TICKS: f64 = 30.0;
+/// Share of the clock error corrected per snapshot
+const CLOCK_CORRECTION: f64 = 0.1;
+
+/// Where the client stands with the server
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientStatus {
+    /// Saying hello, no answer yet
+    Connecting,
+    /// Joined, waiting for the next round
+    Lobby { players: Vec<String>, needed: usize },
+    /// A round is running (or showing its results)
+    Playing,
+    Refused(String),
+    /// The server stopped answering
+    TimedOut,
+}
+
+/// What happened during one `update`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientEvent {
+    RoundStarted,
+    RoundOver,
+}
+
+pub struct NetClient {
+    transport: Box<dyn Transport>,
+    server: SocketAddr,
+    name: String,
+    skin: HoleSkin,
+    pub status: ClientStatus,
+    /// Local view of the current round: rebuilt from its seed, the local hole predicted and
+    /// the others interpolated from snapshots
+    pub sim: Option<Simulation>,
+    /// Seconds other holes are drawn behind the server; more hides more jitter and loss
+    pub interpolation_delay: f32,
+    /// Round being mirrored, 0 before the first
+    round: u32,
+    /// Snapshots received this round, oldest first
+    received: VecDeque<Snapshot>,
+    /// Newest input the server applied, from the newest snapshot
+    input_ack: u32,
+    object_index: HashMap<u32, usize>,
+    /// The server's ticks per second, which the client predicts and sends input at
+    tick_rate: u32,
+    timestep: FixedTimestep,
+    /// Movement for the coming ticks, set by `set_input`
+    input: HoleInput,
+    /// Dash pressed since the last tick
+    pending_dash: bool,
+    /// Sequence number of the newest input
+    input_seq: u32,
+    /// Inputs not yet applied by the server, oldest first
+    unacked: VecDeque<(u32, HoleInput)>,
+    /// Estimated server tick right now, advanced locally and pulled toward each snapshot
+    server_clock: f64,
+    /// Seconds since anything was sent, a keepalive goes out after `RESEND_INTERVAL`
+    since_send: f32,
+    /// Seconds since the server was last heard from
+    silence: f32,
+}
+
+impl NetClient {
+    pub fn new(transport: Box<dyn Transport>, server: SocketAddr, name: &str, skin: HoleSkin) -> Self {
+        Self {
+            transport,
+            server,
+            name: name.to_string(),
+            skin,
+            status: ClientStatus::Connecting,
+            sim: None,
+            interpolation_delay: DEFAULT_INTERPOLATION_DELAY,
+            round: 0,
+            received: VecDeque::new(),
+            input_ack: 0,
+            object_index: HashMap::new(),
+            tick_rate: TICK_RATE,
+            timestep: FixedTimestep::new(),
+            input: HoleInput::default(),
+            pending_dash: false,
+            input_seq: 0,
+            unacked: VecDeque::new(),
+            server_clock: 0.0,
+            // Say hello on the first update
+            since_send: RESEND_INTERVAL,
+            silence: 0.0,
+        }
+    }
+
+    /// Client on an ephemeral UDP port, joining the server at `server`; anything but ideal
+    /// `link` conditions goes through a `SimulatedLink`
+    pub fn connect(server: SocketAddr, name: &str, skin: HoleSkin, link: LinkConditions) -> io::Result<Self> {
+        let local: SocketAddr = if server.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" }.parse().expect("valid address");
+        let udp = UdpTransport::bind(local)?;
+        let transport: Box<dyn Transport> = if link.is_ideal() {
+            Box::new(udp)
+        } else {
+            let seed = udp.local_addr()?.port() as u64;
+            Box::new(SimulatedLink::new(udp, link, seed))
+        };
+        Ok(Self::new(transport, server, name, skin))
+    }
+
+    pub fn server_addr(&self) -> SocketAddr {
+        self.server
+    }
+
+    /// The local player's hole in the current round
+    pub fn slot(&self) -> Option<usize> {
+        self.sim.as_ref().map(|s| s.player_idx)
+    }
+
+    /// Steer with this input from now on; a dash is used on the next tick
+    pub fn set_input(&mut self, input: HoleInput) {
+        self.input = input;
+        self.pending_dash |= input.dash;
+    }
+
+    /// Handle what the server sent, predict and interpolate, and send the inputs; call every frame
+    pub fn update(&mut self, dt: f32) -> Vec<ClientEvent> {
+        let mut events = Vec::new();
+        let newest = self.received.back().map(|s| s.tick);
+        while let Some((from, bytes)) = self.transport.recv() {
+            if from != self.server {
+                continue;
+            }
+            if let Ok(message) = protocol::decode::<ServerMessage>(&bytes) {
+                self.silence = 0.0;
+                self.handle(message, &mut events);
+            }
+        }
+        if self.received.back().map(|s| s.tick) != newest {
+            self.reconcile(&mut events);
+        }
+
+        if matches!(self.status, ClientStatus::Refused(_) | ClientStatus::TimedOut) {
+            return events;
+        }
+        self.silence += dt;
+        if self.silence >= CLIENT_TIMEOUT {
+            self.status = ClientStatus::TimedOut;
+            return events;
+        }
+
+        let ticks = self.timestep.advance(dt);
+        if self.sim.as_ref().is_some_and(|s| !s.game_over) {
+            for _ in 0..ticks {
+                self.input_seq += 1;
+                let input = HoleInput { dash: std::mem::take(&mut self.pending_dash), ..self.input };
+                self.unacked.push_back((self.input_seq, input));
+                self.predict(&input);
+            }
+            if ticks > 0 {
+                self.send_inputs();
+            }
+        }
+        self.server_clock += (dt * self.tick_rate as f32) as f64;
+        self.interpolate();
+        if let Some(sim) = &mut self.sim {
+            swallow::animate_falling_objects(&sim.holes, &mut sim.world.objects, dt);
+        }
+
+        self.since_send += dt;
+        if self.since_send >= RESEND_INTERVAL {
+            if self.status == ClientStatus::Connecting {
+                self.send(&ClientMessage::Hello { name: self.name.clone(), skin: self.skin });
+            } else {
+                self.send_inputs();
+            }
+        }
+        events
+    }
+
+    /// Tell the server we are gone; sent a few times since nothing confirms it
+    pub fn leave(&mut self) {
+        for _ in 0..3 {
+            self.send(&ClientMessage::Bye);
+        }
+    }
+
+    fn handle(&mut self, message: ServerMessage, events: &mut Vec<ClientEvent>) {
+        match message {
+            ServerMessage::Refused { reason } => self.status = ClientStatus::Refused(reason),
+            ServerMessage::Lobby { players, needed, round } => {
+                // Sent before the round we are in started and overtaken by its start
+                if round < self.round {
+                    return;
+                }
+                self.sim = None;
+                self.status = ClientStatus::Lobby { players, needed };
+            }
+            ServerMessage::RoundStart { round, seed, rules, players, slot, tick_rate } => {
+                if round == self.round && self.sim.is_some() {
+                    return;
+                }
+                let mut sim = Simulation::with_players(rules, &players, seed);
+                sim.player_idx = slot;
+                sim.start();
+                self.object_index = sim.world.objects.iter().enumerate().map(|(i, o)| (o.id, i)).collect();
+                self.sim = Some(sim);
+                self.round = round;
+                if tick_rate != self.tick_rate {
+                    self.tick_rate = tick_rate;
+                    self.timestep = FixedTimestep::with_rate(tick_rate);
+                }
+                self.received.clear();
+                self.unacked.clear();
+                self.input_ack = self.input_seq;
+                self.server_clock = 0.0;
+                self.status = ClientStatus::Playing;
+                events.push(ClientEvent::RoundStarted);
+                // Confirm right away so snapshots start flowing
+                self.send_inputs();
+            }
+            ServerMessage::Snapshot { round, input_ack, delta } => {
+                if self.sim.is_none() || round != self.round || self.received.back().is_some_and(|s| s.tick >= delta.tick) {
+                    return;
+                }
+                let baseline = delta.baseline.and_then(|t| self.received.iter().find(|s| s.tick == t));
+                let Some(snapshot) = delta.resolve(baseline) else { return };
+                self.input_ack = input_ack;
+                self.received.push_back(snapshot);
+                if self.received.len() > SNAPSHOT_HISTORY {
+                    self.received.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Take in the newest snapshot: round state, the server clock, and the local hole reset to
+    /// the server's and replayed with the inputs it has not applied yet
+    fn reconcile(&mut self, events: &mut Vec<ClientEvent>) {
+        let (Some(sim), Some(snapshot)) = (&mut self.sim, self.received.back()) else { return };
+        let was_over = sim.game_over;
+        snapshot.apply_round(sim, &self.object_index);
+        if sim.game_over && !was_over {
+            events.push(ClientEvent::RoundOver);
+        }
+
+        let error = snapshot.tick as f64 - self.server_clock;
+        if error.abs() > CLOCK_RESYNC_TICKS {
+            self.server_clock = snapshot.tick as f64;
+        } else {
+            self.server_clock += error * CLOCK_CORRECTION;
+        }
+
+        let slot = sim.player_idx;
+        if let Some(state) = snapshot.holes.get(slot) {
+            state.write(&mut sim.holes[slot]);
+        }
+        let ack = self.input_ack;
+        self.unacked.retain(|&(seq, _)| seq > ack);
+        let replay: Vec<HoleInput> = self.unacked.iter().map(|&(_, input)| input).collect();
+        for input in &replay {
+            self.predict(input);
+        }
+    }
+
+    /// Move the local hole one tick, exactly as the server's `Simulation::step` will
+    fn predict(&mut self, input: &HoleInput) {
+        let Some(sim) = &mut self.sim else { return };
+        let (width, height) = (sim.world.width, sim.world.height);
+        let hole = &mut sim.holes[sim.player_idx];
+        if hole.is_alive {
+            hole.apply_input(input, &sim.mode_rules);
+        }
+        hole.update(self.timestep.step(), width, height, &sim.mode_rules);
+    }
+
+    /// Place the other holes where the server had them `interpolation_delay` seconds ago
+    fn interpolate(&mut self) {
+        let (Some(sim), Some(newest)) = (&mut self.sim, self.received.back()) else { return };
+        let target = self.server_clock - (self.interpolation_delay * self.tick_rate as f32) as f64;
+        // The last snapshot at or before the target, blended toward the one after it
+        let later = self.received.iter().position(|s| s.tick as f64 > target);
+        let states: Vec<_> = match later {
+            Some(0) => self.received[0].holes.clone(),
+            Some(i) => {
+                let (a, b) = (&self.received[i - 1], &self.received[i]);
+                let t = ((target - a.tick as f64) / (b.tick - a.tick) as f64) as f32;
+                a.holes.iter().zip(&b.holes).map(|(a, b)| a.lerp(b, t)).collect()
+            }
+            // Nothing newer yet: hold the newest rather than guess
+            None => newest.holes.clone(),
+        };
+        for (i, (hole, state)) in sim.holes.iter_mut().zip(&states).enumerate() {
+            if i != sim.player_idx {
+                state.write(hole);
+            }
+        }
+        sim.leaderboard.update(&sim.holes);
+    }
+
+    /// Unacknowledged inputs plus the snapshot acknowledgement; also the keepalive
+    fn send_inputs(&mut self) {
+        let skip = self.unacked.len().saturating_sub(MAX_INPUTS_PER_PACKET);
+        let message = ClientMessage::Input {
+            round: self.round,
+            inputs: self.unacked.iter().skip(skip).copied().collect(),
+            ack: self.received.back().map(|s| s.tick),
+        };
+        self.send(&message);
+    }
+
+    fn send(&mut self, message: &ClientMessage) {
+        self.transport.send(self.server, &protocol::encode(message));
+        self.since_send = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::app::settings::Settings;
+    use crate::gameplay::modes::{GameMode, ModeRules};
+    use crate::net::server::{Server, ServerConfig};
+    use crate::time::tick::TICK_DT;
+    use crate::world::objects::ObjectState;
+    use macroquad::math::Vec2;
+
+    /// One real-time frame: the server, then both clients; only the first one steers
+    fn frame(server: &mut Server, clients: &mut [NetClient], steer: HoleInput) {
+        server.update(TICK_DT);
+        for (i, client) in clients.iter_mut().enumerate() {
+            client.set_input(if i == 0 { steer } else { HoleInput::default() });
+            client.update(TICK_DT);
+        }
+        std::thread::sleep(Duration::from_secs_f32(TICK_DT));
+    }
+
+    #[test]
+    fn prediction_and_interpolation_hold_up_over_a_bad_link() {
+        let link = LinkConditions { latency: 0.04, jitter: 0.02, loss: 0.1 };
+        let mut config = ServerConfig::new(ModeRules::new(GameMode::Classic, &Settings::default()), 5);
+        config.min_players = 2;
+        config.rules.bot_count = 0;
+        let udp = UdpTransport::bind("127.0.0.1:0".parse().unwrap()).expect("bind server");
+        let mut server = Server::new(Box::new(SimulatedLink::new(udp, link, 1)), config);
+        let addr = server.local_addr().unwrap();
+        let mut clients = [
+            NetClient::connect(addr, "Ana", HoleSkin::default(), link).expect("bind client"),
+            NetClient::connect(addr, "Bia", HoleSkin::default(), link).expect("bind client"),
+        ];
+
+        for _ in 0..300 {
+            if clients.iter().all(|c| c.status == ClientStatus::Playing) {
+                break;
+            }
+            frame(&mut server, &mut clients, HoleInput::default());
+        }
+        assert!(clients.iter().all(|c| c.status == ClientStatus::Playing));
+        for _ in 0..20 {
+            frame(&mut server, &mut clients, HoleInput::default());
+        }
+        let slot = clients[0].slot().unwrap();
+        let predicted = |c: &NetClient| c.sim.as_ref().unwrap().holes[slot].position();
+        let truth = |s: &Server| s.sim.as_ref().unwrap().holes[slot].position();
+
+        // The mover sees its hole move on the very frame it steers, long before the server does
+        let before = predicted(&clients[0]);
+        let server_before = truth(&server);
+        let steer = HoleInput { move_dir: Vec2::X, dash: false };
+        frame(&mut server, &mut clients, steer);
+        assert!(predicted(&clients[0]).x > before.x);
+        assert_eq!(truth(&server), server_before);
+
+        // The watcher sees the mover glide: never a step back despite jitter and loss
+        let mut seen = predicted(&clients[1]).x;
+        let mut saw_falling = false;
+        for _ in 0..90 {
+            frame(&mut server, &mut clients, steer);
+            let x = predicted(&clients[1]).x;
+            assert!(x >= seen - 0.01, "remote hole stepped back from {} to {}", seen, x);
+            seen = x;
+            saw_falling |= clients[1].sim.as_ref().unwrap().world.objects.iter().any(|o| matches!(o.state, ObjectState::Falling { .. }));
+        }
+
+        // Once everyone stops, prediction and interpolation settle on the server's state
+        for _ in 0..40 {
+            frame(&mut server, &mut clients, HoleInput::default());
+        }
+        let target = truth(&server);
+        assert!(target.x > server_before.x + 50.0);
+        for client in &clients {
+            assert!(predicted(client).distance(target) < 0.5, "{} vs {}", predicted(client), target);
+        }
+        if !Snapshot::capture(server.sim.as_ref().unwrap()).captures.is_empty() {
+            assert!(saw_falling, "captures are animated on the other client");
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! LAN multiplayer - an authoritative server runs the round, clients send input and mirror snapshots
+//!
+//! Only the seed and the rules travel at round start: `World::generate` is deterministic, so
+//! every client rebuilds the same city and the same holes. After that the server sends
+//! delta-compressed snapshots of the holes and of the objects that left the ground. Clients
+//! predict their own hole from their inputs and draw everyone else a little in the past,
+//! blending between snapshots.
+
+pub mod protocol;
+pub mod snapshot;
+pub mod transport;
+pub mod server;
+pub mod client;
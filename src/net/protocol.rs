@@ -0,0 +1,75 @@
+//! Messages between server and clients, bincode behind a magic and a protocol version
+
+use serde::{Deserialize, Serialize};
+use crate::app::storage::{self, StorageError};
+use crate::gameplay::modes::ModeRules;
+use crate::gameplay::simulation::{HoleInput, PlayerSlot};
+use crate::gameplay::skins::HoleSkin;
+use crate::net::snapshot::SnapshotDelta;
+
+/// Magic bytes at the start of every packet
+pub const PROTOCOL_MAGIC: [u8; 4] = *b"HION";
+/// Current protocol version, bump when any message changes; peers on other versions are ignored
+pub const PROTOCOL_VERSION: u32 = 3;
+/// Port used when none is given
+pub const DEFAULT_PORT: u16 = 7777;
+/// Most inputs in one packet; unacknowledged inputs are repeated until the server confirms them
+pub const MAX_INPUTS_PER_PACKET: usize = 30;
+
+/// Client to server
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Ask to join; repeated until the server answers
+    Hello { name: String, skin: HoleSkin },
+    /// One input per client tick with its sequence number, oldest first
+    Input {
+        /// Round the client is playing, tells the server the round start arrived
+        round: u32,
+        inputs: Vec<(u32, HoleInput)>,
+        /// Tick of the newest snapshot received, the baseline for the next delta
+        ack: Option<u64>,
+    },
+    /// Leaving the server
+    Bye,
+}
+
+/// Server to client
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// Not joined, e.g. the server is full
+    Refused { reason: String },
+    /// Joined and waiting for the next round
+    Lobby {
+        players: Vec<String>,
+        needed: usize,
+        /// Rounds started so far; tells a late lobby message from the end of a round
+        round: u32,
+    },
+    /// A round begins: everything needed to rebuild it locally. Repeated until acknowledged
+    RoundStart {
+        round: u32,
+        seed: u64,
+        rules: ModeRules,
+        players: Vec<PlayerSlot>,
+        /// The receiving client's hole
+        slot: usize,
+        /// Simulation ticks per second; the client predicts and sends input at the same rate
+        tick_rate: u32,
+    },
+    /// State of the round
+    Snapshot {
+        round: u32,
+        /// Newest input sequence the server has applied for this client; the client replays
+        /// its later inputs on top of the snapshot to predict its own hole
+        input_ack: u32,
+        delta: SnapshotDelta,
+    },
+}
+
+pub fn encode<T: Serialize>(message: &T) -> Vec<u8> {
+    storage::encode_versioned(PROTOCOL_MAGIC, PROTOCOL_VERSION, message)
+}
+
+pub fn decode<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, StorageError> {
+    storage::decode_versioned(PROTOCOL_MAGIC, PROTOCOL_VERSION, bytes)
+}
@@ -0,0 +1,499 @@
+//! Authoritative round server - runs the simulation with the connected players as its humans
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use crate::app::text_input::{DEFAULT_NAME, TextInput};
+use crate::gameplay::modes::{GameMode, ModeRules, VictoryResult};
+use crate::gameplay::simulation::{HoleInput, PlayerSlot, Simulation};
+use crate::gameplay::skins::HoleSkin;
+use crate::net::protocol::{self, ClientMessage, ServerMessage};
+use crate::net::snapshot::Snapshot;
+use crate::net::transport::{Transport, UdpTransport};
+use crate::time::tick::{FixedTimestep, TICK_RATE};
+
+/// Seconds without a packet before a client is dropped
+pub const CLIENT_TIMEOUT: f32 = 5.0;
+/// Seconds between repeats of messages that must arrive (round starts, lobby status)
+pub const RESEND_INTERVAL: f32 = 0.5;
+/// Snapshots kept as delta baselines, about two seconds at the default rate
+const SNAPSHOT_HISTORY: usize = 64;
+/// Inputs buffered per client to ride out jitter; beyond this the oldest are dropped to keep latency down
+const MAX_QUEUED_INPUTS: usize = 12;
+
+/// How the server runs its rounds
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    pub rules: ModeRules,
+    /// Seed of the first round, the following rounds count up from it
+    pub seed: u64,
+    /// Players needed before a round starts
+    pub min_players: usize,
+    /// Players accepted at once, later ones are refused
+    pub max_players: usize,
+    /// Each round is topped up with bots to this many holes; `None` keeps the rules' bot count
+    pub bot_fill: Option<usize>,
+    /// Simulation ticks per second
+    pub tick_rate: u32,
+    /// Simulation ticks between two snapshots
+    pub snapshot_interval: u32,
+    /// Seconds the final standings stay up before the next round
+    pub intermission: f32,
+}
+
+impl ServerConfig {
+    pub fn new(rules: ModeRules, seed: u64) -> Self {
+        Self {
+            rules,
+            seed,
+            min_players: 1,
+            max_players: 8,
+            bot_fill: None,
+            tick_rate: TICK_RATE,
+            snapshot_interval: 2,
+            intermission: 8.0,
+        }
+    }
+}
+
+/// How a finished round went, one line of a server's log
+#[derive(Clone, Debug, Serialize)]
+pub struct RoundSummary {
+    pub round: u32,
+    pub seed: u64,
+    pub mode: GameMode,
+    /// Unix time the round ended at
+    pub ended_at: u64,
+    /// Seconds played
+    pub duration: f32,
+    pub result: VictoryResult,
+    pub players: usize,
+    pub bots: usize,
+    /// Every hole, best first
+    pub standings: Vec<HoleResult>,
+}
+
+/// One hole's line in a `RoundSummary`
+#[derive(Clone, Debug, Serialize)]
+pub struct HoleResult {
+    pub name: String,
+    pub bot: bool,
+    pub rank: usize,
+    pub radius: f32,
+    pub score: i32,
+    pub eliminations: i32,
+}
+
+impl RoundSummary {
+    fn new(round: u32, sim: &Simulation) -> Self {
+        let mut standings: Vec<HoleResult> = sim.holes.iter().enumerate().map(|(i, h)| HoleResult {
+            name: h.name.clone(),
+            bot: !h.is_player,
+            rank: sim.rank_of(i),
+            radius: h.radius,
+            score: h.score,
+            eliminations: h.eliminations,
+        }).collect();
+        standings.sort_by_key(|h| h.rank);
+        Self {
+            round,
+            seed: sim.seed,
+            mode: sim.mode_rules.mode,
+            ended_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            duration: sim.clock.elapsed,
+            result: sim.result.clone(),
+            players: sim.human_count,
+            bots: sim.holes.len() - sim.human_count,
+            standings,
+        }
+    }
+}
+
+/// A connected player
+struct RemoteClient {
+    addr: SocketAddr,
+    name: String,
+    skin: HoleSkin,
+    /// Hole in the current round, `None` while waiting for the next one
+    slot: Option<usize>,
+    /// The client confirmed the start of the current round
+    in_round: bool,
+    /// Inputs received and not applied yet with their sequence numbers, one per tick
+    queue: VecDeque<(u32, HoleInput)>,
+    /// Movement of the last input applied, kept up while the queue is empty
+    last_input: HoleInput,
+    /// Newest input sequence received
+    received_seq: u32,
+    /// Newest input sequence applied
+    input_seq: u32,
+    /// Newest snapshot the client confirmed, the baseline for its deltas
+    acked_tick: Option<u64>,
+    /// Seconds since the client was last heard from
+    silence: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Phase {
+    /// Waiting for enough players
+    Lobby,
+    Playing,
+    /// Round over, seconds left before the next one
+    Intermission(f32),
+}
+
+pub struct Server {
+    transport: Box<dyn Transport>,
+    pub config: ServerConfig,
+    clients: Vec<RemoteClient>,
+    /// Current round, `None` in the lobby
+    pub sim: Option<Simulation>,
+    /// Rounds started so far, also the id of the current one
+    round: u32,
+    phase: Phase,
+    /// Snapshots sent this round, newest last
+    history: VecDeque<Snapshot>,
+    timestep: FixedTimestep,
+    /// Ticks since the last snapshot went out
+    since_snapshot: u32,
+    resend_timer: f32,
+}
+
+impl Server {
+    pub fn new(transport: Box<dyn Transport>, config: ServerConfig) -> Self {
+        Self {
+            transport,
+            timestep: FixedTimestep::with_rate(config.tick_rate),
+            config,
+            clients: Vec::new(),
+            sim: None,
+            round: 0,
+            phase: Phase::Lobby,
+            history: VecDeque::new(),
+            since_snapshot: 0,
+            resend_timer: 0.0,
+        }
+    }
+
+    /// Server on a UDP port of this machine
+    pub fn bind(addr: SocketAddr, config: ServerConfig) -> io::Result<Self> {
+        Ok(Self::new(Box::new(UdpTransport::bind(addr)?), config))
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.transport.local_addr()
+    }
+
+    /// Id of the current (or last) round
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Serve forever in real time, handing every finished round to `on_round`
+    pub fn run(mut self, mut on_round: impl FnMut(&RoundSummary)) -> ! {
+        let mut last = Instant::now();
+        loop {
+            let now = Instant::now();
+            if let Some(summary) = self.update(now.duration_since(last).as_secs_f32()) {
+                on_round(&summary);
+            }
+            last = now;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Handle packets, advance the round by `dt` seconds of real time and send what is due;
+    /// returns the round that ended during this update, if any
+    pub fn update(&mut self, dt: f32) -> Option<RoundSummary> {
+        while let Some((from, bytes)) = self.transport.recv() {
+            // Packets from other programs or other protocol versions are dropped
+            if let Ok(message) = protocol::decode::<ClientMessage>(&bytes) {
+                self.handle(from, message);
+            }
+        }
+
+        for client in &mut self.clients {
+            client.silence += dt;
+        }
+        self.clients.retain(|c| c.silence < CLIENT_TIMEOUT);
+
+        let mut finished = None;
+        for _ in 0..self.timestep.advance(dt) {
+            if self.phase == Phase::Playing {
+                self.step();
+                if let (Phase::Intermission(_), Some(sim)) = (self.phase, &self.sim) {
+                    finished = Some(RoundSummary::new(self.round, sim));
+                }
+            }
+            self.since_snapshot += 1;
+            if self.since_snapshot >= self.config.snapshot_interval {
+                self.since_snapshot = 0;
+                self.send_snapshots();
+            }
+        }
+
+        match self.phase {
+            Phase::Lobby if self.clients.len() >= self.config.min_players.max(1) => self.start_round(),
+            Phase::Intermission(left) if left - dt <= 0.0 => {
+                if self.clients.len() >= self.config.min_players.max(1) {
+                    self.start_round();
+                } else {
+                    self.phase = Phase::Lobby;
+                    self.sim = None;
+                    for client in &mut self.clients {
+                        client.slot = None;
+                    }
+                }
+            }
+            Phase::Intermission(left) => self.phase = Phase::Intermission(left - dt),
+            _ => {}
+        }
+
+        self.resend_timer -= dt;
+        if self.resend_timer <= 0.0 {
+            self.resend_timer = RESEND_INTERVAL;
+            self.resend();
+        }
+        finished
+    }
+
+    fn handle(&mut self, from: SocketAddr, message: ClientMessage) {
+        let idx = self.clients.iter().position(|c| c.addr == from);
+        match (message, idx) {
+            (ClientMessage::Hello { name, skin }, None) => {
+                if self.clients.len() >= self.config.max_players {
+                    self.send(from, &ServerMessage::Refused { reason: "Server is full".to_string() });
+                    return;
+                }
+                // Held to the rules of a locally typed name, it is drawn over holes and the leaderboard
+                let name = TextInput::name(&name).value().unwrap_or_else(|| DEFAULT_NAME.to_string());
+                self.clients.push(RemoteClient {
+                    addr: from,
+                    name,
+                    skin,
+                    slot: None,
+                    in_round: false,
+                    queue: VecDeque::new(),
+                    last_input: HoleInput::default(),
+                    received_seq: 0,
+                    input_seq: 0,
+                    acked_tick: None,
+                    silence: 0.0,
+                });
+                self.send(from, &self.lobby_message());
+            }
+            // A repeated hello: the answer was lost, the next resend covers it
+            (ClientMessage::Hello { .. }, Some(i)) => self.clients[i].silence = 0.0,
+            (ClientMessage::Input { round, inputs, ack }, Some(i)) => {
+                let current = self.round;
+                let client = &mut self.clients[i];
+                client.silence = 0.0;
+                if round != current || client.slot.is_none() {
+                    return;
+                }
+                client.in_round = true;
+                for (seq, input) in inputs {
+                    if seq > client.received_seq {
+                        client.received_seq = seq;
+                        client.queue.push_back((seq, input));
+                    }
+                }
+                while client.queue.len() > MAX_QUEUED_INPUTS {
+                    // Skipped, but it counts as applied
+                    let (seq, dropped) = client.queue.pop_front().expect("queue is not empty");
+                    client.input_seq = seq;
+                    if let Some((_, next)) = client.queue.front_mut() {
+                        next.dash |= dropped.dash;
+                    }
+                }
+                if let Some(tick) = ack {
+                    client.acked_tick = Some(client.acked_tick.map_or(tick, |t| t.max(tick)));
+                }
+            }
+            (ClientMessage::Bye, Some(i)) => { self.clients.remove(i); }
+            _ => {}
+        }
+    }
+
+    fn start_round(&mut self) {
+        let seed = self.config.seed.wrapping_add(self.round as u64);
+        self.round += 1;
+        let players: Vec<PlayerSlot> = self.clients.iter().map(|c| PlayerSlot::new(&c.name, c.skin)).collect();
+        for (slot, client) in self.clients.iter_mut().enumerate() {
+            client.slot = Some(slot);
+            client.in_round = false;
+            client.queue.clear();
+            client.last_input = HoleInput::default();
+            // Sequences run on across rounds
+            client.input_seq = client.received_seq;
+            client.acked_tick = None;
+        }
+        let mut rules = self.config.rules.clone();
+        if let Some(fill) = self.config.bot_fill.filter(|_| rules.mode.has_bots()) {
+            rules.bot_count = fill.saturating_sub(players.len());
+        }
+        let mut sim = Simulation::with_players(rules, &players, seed);
+        sim.start();
+        self.sim = Some(sim);
+        self.history.clear();
+        self.phase = Phase::Playing;
+        self.resend_timer = 0.0;
+    }
+
+    /// One simulation tick with the next input of every player
+    fn step(&mut self) {
+        let Some(sim) = &mut self.sim else { return };
+        let mut inputs = vec![HoleInput::default(); sim.human_count];
+        for client in &mut self.clients {
+            let Some(slot) = client.slot else { continue };
+            inputs[slot] = match client.queue.pop_front() {
+                Some((seq, input)) => {
+                    client.input_seq = seq;
+                    client.last_input = input;
+                    input
+                }
+                // Late: keep moving the same way, a dash is never repeated
+                None => HoleInput { dash: false, ..client.last_input },
+            };
+        }
+        sim.step(&inputs, self.timestep.step());
+        // Nobody draws the server's effects
+        sim.effects.clear();
+        sim.events.clear();
+        if sim.game_over {
+            self.phase = Phase::Intermission(self.config.intermission);
+        }
+    }
+
+    /// Send every client in the round the current state, as a delta against what it acknowledged
+    fn send_snapshots(&mut self) {
+        let Some(sim) = &self.sim else { return };
+        let snapshot = Snapshot::capture(sim);
+        if self.history.back().is_some_and(|s| s.tick == snapshot.tick) {
+            self.history.pop_back();
+        }
+        self.history.push_back(snapshot);
+        if self.history.len() > SNAPSHOT_HISTORY {
+            self.history.pop_front();
+        }
+        let current = self.history.back().expect("just pushed");
+
+        for client in self.clients.iter().filter(|c| c.in_round) {
+            let baseline = client.acked_tick.and_then(|t| self.history.iter().find(|s| s.tick == t));
+            let message = ServerMessage::Snapshot { round: self.round, input_ack: client.input_seq, delta: current.delta(baseline) };
+            self.transport.send(client.addr, &protocol::encode(&message));
+        }
+    }
+
+    /// Repeat the round start to clients that have not confirmed it, and the lobby to those waiting
+    fn resend(&mut self) {
+        let lobby = self.lobby_message();
+        for client in &self.clients {
+            let message = match (client.slot, &self.sim) {
+                (Some(slot), Some(sim)) if !client.in_round => ServerMessage::RoundStart {
+                    round: self.round,
+                    seed: sim.seed,
+                    rules: sim.mode_rules.clone(),
+                    players: sim.humans().iter().map(|h| PlayerSlot::new(&h.name, h.skin())).collect(),
+                    slot,
+                    tick_rate: self.config.tick_rate,
+                },
+                (None, _) => lobby.clone(),
+                _ => continue,
+            };
+            self.transport.send(client.addr, &protocol::encode(&message));
+        }
+    }
+
+    fn lobby_message(&self) -> ServerMessage {
+        ServerMessage::Lobby {
+            players: self.clients.iter().map(|c| c.name.clone()).collect(),
+            needed: self.config.min_players,
+            round: self.round,
+        }
+    }
+
+    fn send(&mut self, to: SocketAddr, message: &ServerMessage) {
+        self.transport.send(to, &protocol::encode(message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::modes::GameMode;
+    use crate::net::client::{ClientStatus, NetClient};
+    use crate::net::transport::{LinkConditions, MemoryNetwork, SimulatedLink};
+    use crate::time::tick::TICK_DT;
+    use macroquad::math::Vec2;
+
+    #[test]
+    fn two_clients_play_a_round_in_process() {
+        // A perfect link over in-process queues: delivery depends only on the order of the updates
+        let network = MemoryNetwork::default();
+        let link = |seed| Box::new(SimulatedLink::new(network.endpoint(), LinkConditions::default(), seed));
+        let mut config = ServerConfig::new(ModeRules::new(GameMode::Classic, &Settings::default()), 11);
+        config.min_players = 2;
+        let mut server = Server::new(link(0), config);
+        let addr = server.local_addr().unwrap();
+        let mut clients = [
+            NetClient::new(link(1), addr, "Ana", HoleSkin::default()),
+            NetClient::new(link(2), addr, "Bia", HoleSkin::default()),
+        ];
+
+        let pump = |server: &mut Server, clients: &mut [NetClient], frames: usize, input: HoleInput| {
+            for _ in 0..frames {
+                server.update(TICK_DT);
+                for client in clients.iter_mut() {
+                    // Only the first client steers
+                    let own = if client.slot() == Some(0) { input } else { HoleInput::default() };
+                    client.set_input(own);
+                    client.update(TICK_DT);
+                }
+            }
+        };
+
+        pump(&mut server, &mut clients, 60, HoleInput::default());
+        assert!(clients.iter().all(|c| c.status == ClientStatus::Playing));
+        let mut slots: Vec<_> = clients.iter().map(|c| c.slot().unwrap()).collect();
+        slots.sort();
+        assert_eq!(slots, [0, 1]);
+
+        let start = clients[1].sim.as_ref().unwrap().holes[0].position();
+        pump(&mut server, &mut clients, 120, HoleInput { move_dir: Vec2::X, dash: false });
+        // Let the last snapshots arrive
+        pump(&mut server, &mut clients, 10, HoleInput::default());
+
+        let truth = Snapshot::capture(server.sim.as_ref().unwrap());
+        for client in &clients {
+            let mirror = client.sim.as_ref().unwrap();
+            assert!(mirror.holes[0].position().x > start.x + 10.0, "the first player's moves reach everyone");
+            let seen = Snapshot::capture(mirror);
+            // Mirrors trail the server by at most a few ticks
+            assert!(truth.tick - seen.tick <= 8);
+            assert!(seen.captures.iter().all(|c| truth.captures.binary_search_by_key(&c.object, |t| t.object).is_ok()));
+        }
+    }
+
+    #[test]
+    fn joining_names_are_held_to_the_local_name_rules() {
+        let network = MemoryNetwork::default();
+        let mut config = ServerConfig::new(ModeRules::new(GameMode::Classic, &Settings::default()), 11);
+        config.min_players = 4;
+        let mut server = Server::new(Box::new(network.endpoint()), config);
+        let addr = server.local_addr().unwrap();
+        let mut clients: Vec<NetClient> = [" <Ana>\n ", "Bia the Very Long Name", "!?*"].into_iter()
+            .map(|name| NetClient::new(Box::new(network.endpoint()), addr, name, HoleSkin::default()))
+            .collect();
+        for _ in 0..10 {
+            server.update(TICK_DT);
+            for client in &mut clients {
+                client.update(TICK_DT);
+            }
+        }
+        let names: Vec<&str> = server.clients.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Ana", "Bia the Very", DEFAULT_NAME]);
+    }
+}
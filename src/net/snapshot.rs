@@ -0,0 +1,252 @@
+//! Round snapshots - what clients need to draw the server's round, and deltas between two of them
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::gameplay::hole::Hole;
+use crate::gameplay::modes::VictoryResult;
+use crate::gameplay::simulation::Simulation;
+use crate::gameplay::swallow;
+use crate::gameplay::zone::SafeZone;
+use crate::world::objects::ObjectState;
+
+/// Network view of a hole; names, colors and skins are known from the round start
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HoleState {
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub is_alive: bool,
+    pub score: i32,
+    pub eliminations: i32,
+    pub dash_cooldown: f32,
+    pub dash_active: f32,
+    pub respawn_timer: f32,
+    pub invincible: f32,
+}
+
+/// An object that left the ground, and the hole that took it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capture {
+    pub object: u32,
+    /// Id of the captor while the object is still falling, `None` once it is gone
+    pub captor: Option<u32>,
+}
+
+/// State of the round at one tick
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub tick: u64,
+    pub remaining: f32,
+    pub elapsed: f32,
+    /// Every hole, in simulation order
+    pub holes: Vec<HoleState>,
+    /// Objects no longer on the ground (falling or eaten), by ascending id
+    pub captures: Vec<Capture>,
+    pub zone: Option<SafeZone>,
+    pub game_over: bool,
+    pub result: VictoryResult,
+}
+
+/// A snapshot as sent: only what changed since a baseline the client acknowledged
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    pub tick: u64,
+    /// Tick of the snapshot this delta applies to, `None` for a complete snapshot
+    pub baseline: Option<u64>,
+    pub remaining: f32,
+    pub elapsed: f32,
+    /// Holes that changed, by index
+    pub holes: Vec<(u16, HoleState)>,
+    /// Objects that left the ground since the baseline (objects never come back within a round)
+    pub captures: Vec<Capture>,
+    pub zone: Option<SafeZone>,
+    pub game_over: bool,
+    pub result: VictoryResult,
+}
+
+impl Snapshot {
+    /// Capture the state of a running round
+    pub fn capture(sim: &Simulation) -> Self {
+        let holes = sim.holes.iter().map(|h| HoleState {
+            x: h.x,
+            y: h.y,
+            radius: h.radius,
+            is_alive: h.is_alive,
+            score: h.score,
+            eliminations: h.eliminations,
+            dash_cooldown: h.dash_cooldown,
+            dash_active: h.dash_active,
+            respawn_timer: h.respawn_timer,
+            invincible: h.invincible,
+        }).collect();
+        let mut captures: Vec<Capture> = sim.world.objects.iter()
+            .filter(|o| o.consumed || o.state != ObjectState::Normal)
+            .map(|o| Capture { object: o.id, captor: o.captor() })
+            .collect();
+        captures.sort_unstable_by_key(|c| c.object);
+        Self {
+            tick: sim.tick,
+            remaining: sim.clock.remaining,
+            elapsed: sim.clock.elapsed,
+            holes,
+            captures,
+            zone: sim.zone.clone(),
+            game_over: sim.game_over,
+            result: sim.result.clone(),
+        }
+    }
+
+    /// Encode against `baseline`, or completely when there is none
+    pub fn delta(&self, baseline: Option<&Snapshot>) -> SnapshotDelta {
+        let (holes, captures) = match baseline {
+            Some(base) => {
+                let holes = self.holes.iter().enumerate()
+                    .filter(|&(i, h)| base.holes.get(i) != Some(h))
+                    .map(|(i, h)| (i as u16, *h))
+                    .collect();
+                // Both lists are sorted and the baseline's objects are a subset of ours
+                let captures = self.captures.iter()
+                    .filter(|c| base.captures.binary_search_by_key(&c.object, |b| b.object).is_err())
+                    .copied()
+                    .collect();
+                (holes, captures)
+            }
+            None => (self.holes.iter().enumerate().map(|(i, h)| (i as u16, *h)).collect(), self.captures.clone()),
+        };
+        SnapshotDelta {
+            tick: self.tick,
+            baseline: baseline.map(|b| b.tick),
+            remaining: self.remaining,
+            elapsed: self.elapsed,
+            holes,
+            captures,
+            zone: self.zone.clone(),
+            game_over: self.game_over,
+            result: self.result.clone(),
+        }
+    }
+
+    /// Everything but the holes into a mirror of the round built from the same seed and players;
+    /// `object_index` maps object ids to their index in the world. New captures start falling
+    /// locally, with the effects a local round would show.
+    pub fn apply_round(&self, sim: &mut Simulation, object_index: &HashMap<u32, usize>) {
+        for capture in &self.captures {
+            let Some(obj) = object_index.get(&capture.object).and_then(|&i| sim.world.objects.get_mut(i)) else { continue };
+            if obj.state != ObjectState::Normal {
+                continue;
+            }
+            match capture.captor.and_then(|id| sim.holes.iter().find(|h| h.id == id)) {
+                Some(hole) => {
+                    obj.start_falling(hole.id, hole.x, hole.y);
+                    swallow::capture_effects(obj, hole, &mut sim.effects);
+                }
+                // Eaten before we heard of it
+                None => {
+                    obj.consumed = true;
+                    obj.state = ObjectState::Consumed;
+                }
+            }
+        }
+        sim.tick = self.tick;
+        sim.clock.remaining = self.remaining;
+        sim.clock.elapsed = self.elapsed;
+        sim.zone = self.zone.clone();
+        sim.game_over = self.game_over;
+        sim.result = self.result.clone();
+    }
+}
+
+impl HoleState {
+    /// Write into the mirror's hole
+    pub fn write(&self, hole: &mut Hole) {
+        hole.x = self.x;
+        hole.y = self.y;
+        hole.radius = self.radius;
+        hole.area = std::f32::consts::PI * self.radius * self.radius;
+        hole.peak_radius = hole.peak_radius.max(self.radius);
+        hole.is_alive = self.is_alive;
+        hole.score = self.score;
+        hole.eliminations = self.eliminations;
+        hole.dash_cooldown = self.dash_cooldown;
+        hole.dash_active = self.dash_active;
+        hole.respawn_timer = self.respawn_timer;
+        hole.invincible = self.invincible;
+    }
+
+    /// Blend toward a later state, `t` from 0 (self) to 1 (`later`). Position and size slide;
+    /// the rest comes from `later`. A hole that died or respawned in between jumps.
+    pub fn lerp(&self, later: &HoleState, t: f32) -> HoleState {
+        if !self.is_alive || !later.is_alive {
+            return *later;
+        }
+        HoleState {
+            x: self.x + (later.x - self.x) * t,
+            y: self.y + (later.y - self.y) * t,
+            radius: self.radius + (later.radius - self.radius) * t,
+            ..*later
+        }
+    }
+}
+
+impl SnapshotDelta {
+    /// Rebuild the full snapshot; `None` if the delta needs a baseline that is not the one given
+    pub fn resolve(self, baseline: Option<&Snapshot>) -> Option<Snapshot> {
+        let (mut holes, mut captures) = match (self.baseline, baseline) {
+            (None, _) => (Vec::new(), Vec::new()),
+            (Some(tick), Some(base)) if base.tick == tick => (base.holes.clone(), base.captures.clone()),
+            _ => return None,
+        };
+        for (i, state) in self.holes {
+            let i = i as usize;
+            if i >= holes.len() {
+                holes.resize(i + 1, state);
+            }
+            holes[i] = state;
+        }
+        captures.extend(self.captures);
+        captures.sort_unstable_by_key(|c| c.object);
+        captures.dedup_by_key(|c| c.object);
+        Some(Snapshot {
+            tick: self.tick,
+            remaining: self.remaining,
+            elapsed: self.elapsed,
+            holes,
+            captures,
+            zone: self.zone,
+            game_over: self.game_over,
+            result: self.result,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::settings::Settings;
+    use crate::gameplay::modes::{GameMode, ModeRules};
+    use crate::gameplay::simulation::HoleInput;
+    use crate::gameplay::skins::HoleSkin;
+    use crate::time::tick::TICK_DT;
+    use macroquad::math::Vec2;
+
+    #[test]
+    fn delta_against_a_baseline_rebuilds_the_snapshot() {
+        let rules = ModeRules::new(GameMode::Classic, &Settings::default());
+        let mut sim = Simulation::new(rules, "P", HoleSkin::default(), 3);
+        sim.start();
+        let base = Snapshot::capture(&sim);
+        for _ in 0..300 {
+            sim.step(&[HoleInput { move_dir: Vec2::X, dash: false }], TICK_DT);
+        }
+        let now = Snapshot::capture(&sim);
+        assert!(now.captures.len() > base.captures.len());
+
+        let delta = now.delta(Some(&base));
+        assert!(delta.captures.len() < now.captures.len() || base.captures.is_empty());
+        let rebuilt = delta.clone().resolve(Some(&base)).expect("matching baseline");
+        assert_eq!(rebuilt.holes, now.holes);
+        assert_eq!(rebuilt.captures, now.captures);
+        // A delta is useless against any other baseline
+        assert!(delta.resolve(Some(&now)).is_none());
+    }
+}
@@ -0,0 +1,174 @@
+//! Datagram transport - non-blocking UDP behind a small trait so tests and tools can wrap it
+
+#[cfg(test)]
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Largest datagram UDP can carry over IPv4
+pub const MAX_DATAGRAM: usize = 65_507;
+
+/// Unreliable, unordered datagrams; both ends poll it every frame or tick
+pub trait Transport: Send {
+    fn send(&mut self, to: SocketAddr, bytes: &[u8]);
+    /// Next datagram that arrived, `None` when there is nothing waiting
+    fn recv(&mut self) -> Option<(SocketAddr, Vec<u8>)>;
+    fn local_addr(&self) -> io::Result<SocketAddr>;
+}
+
+/// Non-blocking UDP socket
+pub struct UdpTransport {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+}
+
+impl UdpTransport {
+    /// Bind to an address, `0.0.0.0:0` for a client's ephemeral port
+    pub fn bind(addr: SocketAddr) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket, buf: vec![0; MAX_DATAGRAM] })
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&mut self, to: SocketAddr, bytes: &[u8]) {
+        // Datagrams may be lost anyway; a failed send is just one more loss
+        if let Err(e) = self.socket.send_to(bytes, to) {
+            if e.kind() != io::ErrorKind::WouldBlock {
+                eprintln!("Could not send to {}: {}", to, e);
+            }
+        }
+    }
+
+    fn recv(&mut self) -> Option<(SocketAddr, Vec<u8>)> {
+        // A peer that went away makes some systems report an error on the next read; it is
+        // treated like an empty socket and the next poll carries on
+        let (len, from) = self.socket.recv_from(&mut self.buf).ok()?;
+        Some((from, self.buf[..len].to_vec()))
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+}
+
+/// Network conditions a `SimulatedLink` imposes on what it sends
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    /// One-way delay in seconds
+    pub latency: f32,
+    /// Extra random delay of up to this many seconds; datagrams overtake each other
+    pub jitter: f32,
+    /// Fraction of datagrams dropped (0-1)
+    pub loss: f32,
+}
+
+impl LinkConditions {
+    pub fn is_ideal(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A transport that delays, reorders and drops its outgoing datagrams, to play and test
+/// over loopback as if over a bad network. Put one on each end to affect both directions.
+pub struct SimulatedLink<T: Transport> {
+    inner: T,
+    pub conditions: LinkConditions,
+    /// Datagrams waiting for their delivery time
+    queue: Vec<(Instant, SocketAddr, Vec<u8>)>,
+    rng: ChaCha8Rng,
+}
+
+impl<T: Transport> SimulatedLink<T> {
+    pub fn new(inner: T, conditions: LinkConditions, seed: u64) -> Self {
+        Self { inner, conditions, queue: Vec::new(), rng: ChaCha8Rng::seed_from_u64(seed) }
+    }
+
+    /// Hand every datagram whose time has come to the real transport
+    fn flush(&mut self) {
+        let now = Instant::now();
+        let mut i = 0;
+        while i < self.queue.len() {
+            if self.queue[i].0 <= now {
+                let (_, to, bytes) = self.queue.swap_remove(i);
+                self.inner.send(to, &bytes);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+impl<T: Transport> Transport for SimulatedLink<T> {
+    fn send(&mut self, to: SocketAddr, bytes: &[u8]) {
+        self.flush();
+        if self.rng.gen::<f32>() < self.conditions.loss {
+            return;
+        }
+        let delay = self.conditions.latency + self.rng.gen::<f32>() * self.conditions.jitter;
+        let due = Instant::now() + Duration::from_secs_f32(delay.max(0.0));
+        self.queue.push((due, to, bytes.to_vec()));
+    }
+
+    fn recv(&mut self) -> Option<(SocketAddr, Vec<u8>)> {
+        // Both ends poll every frame or tick, which is also when delayed datagrams go out
+        self.flush();
+        self.inner.recv()
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+}
+
+/// In-process datagrams for tests: endpoints of one network deliver straight into each other's
+/// queues, in order and without loss, so a test does not depend on the machine's sockets or load
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemoryNetwork {
+    queues: Arc<Mutex<HashMap<SocketAddr, Inbox>>>,
+}
+
+/// Datagrams waiting for one endpoint, with their senders
+#[cfg(test)]
+type Inbox = VecDeque<(SocketAddr, Vec<u8>)>;
+
+#[cfg(test)]
+impl MemoryNetwork {
+    /// A new endpoint with its own address on this network
+    pub fn endpoint(&self) -> MemoryTransport {
+        let mut queues = self.queues.lock().unwrap();
+        let addr = SocketAddr::from(([127, 0, 0, 1], 1 + queues.len() as u16));
+        queues.insert(addr, Default::default());
+        MemoryTransport { addr, network: self.clone() }
+    }
+}
+
+#[cfg(test)]
+pub struct MemoryTransport {
+    addr: SocketAddr,
+    network: MemoryNetwork,
+}
+
+#[cfg(test)]
+impl Transport for MemoryTransport {
+    fn send(&mut self, to: SocketAddr, bytes: &[u8]) {
+        if let Some(queue) = self.network.queues.lock().unwrap().get_mut(&to) {
+            queue.push_back((self.addr, bytes.to_vec()));
+        }
+    }
+
+    fn recv(&mut self) -> Option<(SocketAddr, Vec<u8>)> {
+        self.network.queues.lock().unwrap().get_mut(&self.addr)?.pop_front()
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.addr)
+    }
+}
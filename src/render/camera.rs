@@ -0,0 +1,67 @@
+//! Camera - follows a hole or moves freely over the world
+
+use macroquad::prelude::*;
+
+/// Camera state
+pub struct Camera {
+    pub x: f32,
+    pub y: f32,
+    pub zoom: f32,
+    pub target_zoom: f32,
+    /// Screen region the camera draws into (split screen), `None` for the whole screen
+    pub viewport: Option<Rect>,
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self { x: 0.0, y: 0.0, zoom: 1.0, target_zoom: 1.0, viewport: None }
+    }
+
+    /// Size of the region the camera draws into, in screen pixels
+    pub fn view_size(&self) -> (f32, f32) {
+        match self.viewport {
+            Some(rect) => (rect.w, rect.h),
+            None => (screen_width(), screen_height()),
+        }
+    }
+
+    /// Smoothly track a hole, zooming out as it grows
+    pub fn follow(&mut self, target_x: f32, target_y: f32, hole_radius: f32, _dt: f32, smoothing: f32) {
+        let (sw, sh) = self.view_size();
+        
+        // Target camera position (center on hole)
+        let target_cx = target_x - sw / (2.0 * self.zoom);
+        let target_cy = target_y - sh / (2.0 * self.zoom);
+        
+        // Smooth follow
+        self.x += (target_cx - self.x) * smoothing.min(1.0);
+        self.y += (target_cy - self.y) * smoothing.min(1.0);
+        
+        // Dynamic zoom based on hole size
+        self.target_zoom = (50.0 / hole_radius).clamp(0.4, 1.2);
+        self.zoom += (self.target_zoom - self.zoom) * 0.02;
+    }
+
+    /// Move freely by a screen-space offset (used when detached from any hole)
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.x += dx / self.zoom;
+        self.y += dy / self.zoom;
+    }
+
+    /// Zoom around the view center
+    pub fn zoom_by(&mut self, factor: f32) {
+        let (sw, sh) = self.view_size();
+        let cx = self.x + sw / (2.0 * self.zoom);
+        let cy = self.y + sh / (2.0 * self.zoom);
+        self.zoom = (self.zoom * factor).clamp(0.2, 2.0);
+        self.target_zoom = self.zoom;
+        self.x = cx - sw / (2.0 * self.zoom);
+        self.y = cy - sh / (2.0 * self.zoom);
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
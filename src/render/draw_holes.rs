@@ -47,8 +47,11 @@ pub fn draw_hole(
     // Draw hole layers (fake depth)
     draw_hole_depth(x, y, r, hole.color, alpha);
     
+    // Animated patterns inside the hole
+    draw_hole_pattern_fill(x, y, r, hole.color, hole.skin_pattern, hole.id, alpha, hole.pulse_timer);
+
     // Draw border with potential pattern
-    draw_hole_border(x, y, r, hole.color, hole.skin_pattern, alpha, hole.pulse_timer);
+    draw_hole_border(x, y, r, hole.color, hole.skin_pattern, hole.border_style, alpha, hole.pulse_timer);
 
     // Draw name label
     draw_hole_label(x, y, r, &hole.name, hole.is_player, theme);
@@ -59,7 +62,7 @@ pub fn draw_hole(
     }
 }
 
-fn draw_hole_depth(x: f32, y: f32, r: f32, color: Color, alpha: f32) {
+fn draw_hole_depth(x: f32, y: f32, r: f32, _color: Color, alpha: f32) {
     // Outer dark ring (shadow)
     let shadow = Color::new(0.0, 0.0, 0.0, 0.8 * alpha);
     draw_circle(x, y, r * 1.02, shadow);
@@ -82,54 +85,127 @@ fn draw_hole_depth(x: f32, y: f32, r: f32, color: Color, alpha: f32) {
     draw_circle(x, y, r * 0.2, Color::new(0.0, 0.0, 0.0, alpha));
 }
 
-fn draw_hole_border(x: f32, y: f32, r: f32, color: Color, pattern: u8, alpha: f32, pulse_timer: f32) {
-    let border_width = (r * 0.08).max(3.0);
-    
+#[allow(clippy::too_many_arguments)]
+fn draw_hole_pattern_fill(x: f32, y: f32, r: f32, color: Color, pattern: u8, seed: u32, alpha: f32, pulse_timer: f32) {
+    match pattern {
+        6 => {
+            // Spiral: three arms slowly turning toward the center
+            let arm_color = Color::new(color.r, color.g, color.b, 0.3 * alpha);
+            let steps = 24;
+            for arm in 0..3 {
+                let base = arm as f32 * std::f32::consts::TAU / 3.0 - pulse_timer * 1.5;
+                let mut prev = vec2(x, y);
+                for i in 1..=steps {
+                    let t = i as f32 / steps as f32;
+                    let angle = base + t * 4.0;
+                    let p = vec2(x, y) + Vec2::from_angle(angle) * r * 0.9 * t;
+                    draw_line(prev.x, prev.y, p.x, p.y, (r * 0.04).max(1.5), arm_color);
+                    prev = p;
+                }
+            }
+        }
+        7 => {
+            // Starfield: twinkling points, placed from the hole id so they stay put
+            let mut state = seed.wrapping_mul(2654435761).wrapping_add(1);
+            let mut next = || {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as f32 / u32::MAX as f32
+            };
+            for k in 0..14 {
+                let angle = next() * std::f32::consts::TAU + pulse_timer * 0.15;
+                let dist = next().sqrt() * r * 0.85;
+                let twinkle = 0.5 + 0.5 * (pulse_timer * 3.0 + k as f32 * 1.7).sin();
+                let star = Color::new(0.9, 0.9, 1.0, twinkle * alpha);
+                draw_circle(x + angle.cos() * dist, y + angle.sin() * dist, (r * 0.025).max(1.0) * (0.6 + next()), star);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Color of one border segment for a pattern
+fn pattern_color(pattern: u8, color: Color, segment: usize, angle: f32, pulse_timer: f32, alpha: f32) -> Color {
+    let shade = |k: f32| Color::new(color.r * k, color.g * k, color.b * k, alpha);
+    match pattern {
+        // Stripes
+        1 => shade(if segment % 4 < 2 { 1.0 } else { 0.5 }),
+        // Wave
+        2 => shade(0.7 + (angle * 8.0 + pulse_timer * 5.0).sin() * 0.3),
+        // Dots (every other segment brighter)
+        3 => shade(if segment.is_multiple_of(2) { 1.0 } else { 0.4 }),
+        // Gradient from the hole color to a pale tint of it
+        4 => {
+            let t = (angle.cos() + 1.0) / 2.0 * 0.6;
+            Color::new(color.r + (1.0 - color.r) * t, color.g + (1.0 - color.g) * t, color.b + (1.0 - color.b) * t, alpha)
+        }
+        // Rainbow running around the ring
+        5 => {
+            let c = macroquad::color::hsl_to_rgb((angle / std::f32::consts::TAU + pulse_timer * 0.25).fract(), 0.9, 0.6);
+            Color::new(c.r, c.g, c.b, alpha)
+        }
+        // Spiral: a bright band sweeping around
+        6 => shade(0.5 + 0.5 * (angle * 3.0 - pulse_timer * 4.5).sin().max(0.0)),
+        // Starfield: deep tint
+        7 => shade(0.75),
+        _ => shade(1.0), // Solid
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_hole_border(x: f32, y: f32, r: f32, color: Color, pattern: u8, style: u8, alpha: f32, pulse_timer: f32) {
+    let base_width = (r * 0.08).max(3.0);
+    let border_width = match style {
+        1 => base_width * 1.8, // Bold
+        2 => base_width * 0.7, // Neon
+        _ => base_width,
+    };
+
     // Pulse effect
     let pulse = 1.0 + (pulse_timer * 3.0).sin() * 0.02;
     let effective_r = r * pulse;
-    
+
     // Main border
     let segments = 64;
     for i in 0..segments {
         let angle1 = (i as f32 / segments as f32) * std::f32::consts::TAU;
         let angle2 = ((i + 1) as f32 / segments as f32) * std::f32::consts::TAU;
-        
+
         let x1 = x + effective_r * angle1.cos();
         let y1 = y + effective_r * angle1.sin();
         let x2 = x + effective_r * angle2.cos();
         let y2 = y + effective_r * angle2.sin();
-        
-        // Pattern variation
-        let pattern_alpha = match pattern {
-            1 => {
-                // Stripes
-                if i % 4 < 2 { 1.0 } else { 0.5 }
-            }
-            2 => {
-                // Wave
-                0.7 + (angle1 * 8.0 + pulse_timer * 5.0).sin() * 0.3
-            }
-            3 => {
-                // Dots (every other segment brighter)
-                if i % 2 == 0 { 1.0 } else { 0.4 }
-            }
-            _ => 1.0, // Solid
-        };
-        
-        let seg_color = Color::new(
-            color.r * pattern_alpha,
-            color.g * pattern_alpha,
-            color.b * pattern_alpha,
-            alpha,
-        );
-        
+
+        let seg_color = pattern_color(pattern, color, i, angle1, pulse_timer, alpha);
         draw_line(x1, y1, x2, y2, border_width, seg_color);
+
+        // Double ring: a thinner copy of the pattern outside the main one
+        if style == 3 {
+            let outer = effective_r + border_width * 2.2;
+            draw_line(
+                x + outer * angle1.cos(), y + outer * angle1.sin(),
+                x + outer * angle2.cos(), y + outer * angle2.sin(),
+                border_width * 0.5, seg_color,
+            );
+        }
     }
 
     // Glow effect
-    let glow_color = Color::new(color.r, color.g, color.b, 0.2 * alpha);
-    draw_circle_lines(x, y, effective_r + border_width, border_width * 0.5, glow_color);
+    match style {
+        // Neon: layered glow fading outward
+        2 => {
+            for layer in 1..=3 {
+                let glow_color = Color::new(color.r, color.g, color.b, 0.25 / layer as f32 * alpha);
+                draw_circle_lines(x, y, effective_r + border_width * layer as f32 * 1.5, border_width * 1.2, glow_color);
+            }
+        }
+        3 => {}
+        _ => {
+            let glow_color = Color::new(color.r, color.g, color.b, 0.2 * alpha);
+            draw_circle_lines(x, y, effective_r + border_width, border_width * 0.5, glow_color);
+        }
+    }
 }
 
 fn draw_hole_label(x: f32, y: f32, r: f32, name: &str, is_player: bool, theme: &Theme) {
@@ -182,7 +258,7 @@ fn draw_dash_trail(x: f32, y: f32, r: f32, color: Color, velocity: Vec2) {
 /// Draw respawn indicator for dead hole
 pub fn draw_respawn_indicator(
     hole: &Hole,
-    theme: &Theme,
+    _theme: &Theme,
     camera_x: f32,
     camera_y: f32,
     zoom: f32,
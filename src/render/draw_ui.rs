@@ -2,11 +2,24 @@
 
 use macroquad::prelude::*;
 use crate::render::theme::{Theme, draw_rounded_rect, draw_rounded_rect_shadow, ease_out_back};
-use crate::gameplay::scoring::LeaderboardEntry;
-use crate::gameplay::modes::GameMode;
+use crate::gameplay::scoring::{LeaderboardEntry, Standing};
+use crate::gameplay::modes::{Elimination, GameMode, VictoryResult};
+use crate::gameplay::zone::SafeZone;
+use crate::gameplay::hole::{player_color, Hole};
+use crate::gameplay::skins::HoleSkin;
+use crate::render::draw_holes::draw_hole;
+use crate::app::settings::{Settings, SettingId, SettingKind};
+use crate::app::lobby::{MatchSetup, LobbyField};
+use crate::app::achievements::Achievement;
+use crate::app::daily_history::DailyHistory;
+use crate::app::highscores::{HighScoreField, HighScoreFilter, HighScores};
+use crate::app::text_input::{MAX_NAME_LEN, OnScreenKeyboard, OskKey, TextInput};
+use crate::gameplay::daily::DailyChallenge;
+use crate::net::client::ClientStatus;
+use crate::app::profile::{PlayerProfile, XpGain, level_for_xp, level_progress, xp_for_level};
 
 /// Draw the main menu
-pub fn draw_menu(theme: &Theme, selection: usize, animation_time: f32) {
+pub fn draw_menu(theme: &Theme, items: &[&str], selection: usize, animation_time: f32) {
     let sw = screen_width();
     let sh = screen_height();
 
@@ -22,9 +35,8 @@ pub fn draw_menu(theme: &Theme, selection: usize, animation_time: f32) {
     draw_text(title, title_x + 4.0, title_y + 4.0, title_size, Color::new(0.0, 0.0, 0.0, 0.5));
     draw_text(title, title_x, title_y, title_size, theme.palette.ui_accent);
 
-    let items = ["PLAY", "SETTINGS", "QUIT"];
     let item_height = 60.0;
-    let start_y = sh * 0.45;
+    let start_y = sh * 0.45 - (items.len() as f32 - 3.0).max(0.0) * item_height / 2.0;
 
     for (i, item) in items.iter().enumerate() {
         let y = start_y + i as f32 * item_height;
@@ -36,7 +48,7 @@ pub fn draw_menu(theme: &Theme, selection: usize, animation_time: f32) {
     draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 40.0, theme.font_size_small, theme.palette.ui_text_secondary);
 }
 
-fn draw_grid_background(theme: &Theme, time: f32) {
+fn draw_grid_background(_theme: &Theme, time: f32) {
     let sw = screen_width();
     let sh = screen_height();
     let grid_size = 50.0;
@@ -68,7 +80,7 @@ fn draw_menu_item(theme: &Theme, text: &str, x: f32, y: f32, selected: bool, tim
 }
 
 /// Draw mode selection screen
-pub fn draw_mode_select(theme: &Theme, selection: usize, animation_time: f32) {
+pub fn draw_mode_select(theme: &Theme, selection: usize, _animation_time: f32) {
     let sw = screen_width();
     let sh = screen_height();
     draw_rectangle(0.0, 0.0, sw, sh, Color::new(0.0, 0.0, 0.0, 0.7));
@@ -77,9 +89,9 @@ pub fn draw_mode_select(theme: &Theme, selection: usize, animation_time: f32) {
     let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
     draw_text(title, sw / 2.0 - title_dims.width / 2.0, sh * 0.2, theme.font_size_large, theme.palette.ui_text);
 
-    let modes = [("CLASSIC", "2 min, biggest wins!"), ("BATTLE", "Last standing!"), ("SOLO", "100% city!")];
+    let modes = [("CLASSIC", "2 min, biggest wins!"), ("BATTLE", "Last standing!"), ("SOLO", "100% city!"), ("DAILY", "Same city for all!")];
     let card_width = 200.0;
-    let total_width = card_width * 3.0 + 40.0;
+    let total_width = card_width * modes.len() as f32 + 20.0 * (modes.len() - 1) as f32;
     let start_x = sw / 2.0 - total_width / 2.0;
     let card_y = sh * 0.4;
 
@@ -99,17 +111,233 @@ pub fn draw_mode_select(theme: &Theme, selection: usize, animation_time: f32) {
     draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 40.0, theme.font_size_small, theme.palette.ui_text_secondary);
 }
 
-/// Draw the HUD during gameplay
-pub fn draw_hud(theme: &Theme, timer: f32, leaderboard: &[LeaderboardEntry], player_rank: Option<usize>, player_size: f32, mode: GameMode, city_consumed: f32, dash_cooldown: f32, dash_cooldown_max: f32) {
+/// Draw today's daily challenge: modifiers, best score, streak and the calendar of the last weeks
+pub fn draw_daily(theme: &Theme, challenge: &DailyChallenge, history: &DailyHistory, selection: usize, animation_time: f32) {
     let sw = screen_width();
     let sh = screen_height();
+    draw_rectangle(0.0, 0.0, sw, sh, theme.palette.background);
+    draw_grid_background(theme, animation_time);
+
+    let title = format!("DAILY CHALLENGE - {}", challenge.date);
+    let title_dims = measure_text(&title, None, theme.font_size_large as u16, 1.0);
+    draw_text(&title, sw / 2.0 - title_dims.width / 2.0, sh * 0.1, theme.font_size_large, theme.palette.ui_text);
+
+    let w = 520.0;
+    let x = sw / 2.0 - w / 2.0;
+    let mut y = sh * 0.1 + 45.0;
+    draw_text(&format!("{} with", challenge.mode.name()), x, y, theme.font_size_medium, theme.palette.ui_accent);
+    for modifier in &challenge.modifiers {
+        y += 30.0;
+        draw_text(modifier.name(), x + 20.0, y, theme.font_size_medium * 0.8, theme.palette.ui_text);
+        let desc_dims = measure_text(modifier.description(), None, theme.font_size_small as u16, 1.0);
+        draw_text(modifier.description(), x + w - desc_dims.width, y, theme.font_size_small, theme.palette.ui_text_secondary);
+    }
+
+    y += 40.0;
+    let best = match history.best(challenge.date) {
+        Some(score) => format!("Today's best: {}", score),
+        None => "Not played today".to_string(),
+    };
+    draw_text(&best, x, y, theme.font_size_medium * 0.8, theme.palette.ui_text);
+    let streak = history.streak(challenge.date);
+    let streak_text = format!("Streak: {} day{}", streak, if streak == 1 { "" } else { "s" });
+    let streak_dims = measure_text(&streak_text, None, (theme.font_size_medium * 0.8) as u16, 1.0);
+    draw_text(&streak_text, x + w - streak_dims.width, y, theme.font_size_medium * 0.8, theme.palette.ui_accent);
+
+    // Calendar of the last four weeks, Monday first, today in the bottom row
+    let cols = 7;
+    let rows = 4;
+    let cell = 36.0;
+    let gap = 6.0;
+    let grid_w = cols as f32 * (cell + gap) - gap;
+    let gx = sw / 2.0 - grid_w / 2.0;
+    let gy = y + 30.0;
+    for (i, name) in ["M", "T", "W", "T", "F", "S", "S"].iter().enumerate() {
+        let dims = measure_text(name, None, 14, 1.0);
+        draw_text(name, gx + i as f32 * (cell + gap) + cell / 2.0 - dims.width / 2.0, gy, 14.0, theme.palette.ui_text_secondary);
+    }
+    let first = challenge.date.add_days(-((challenge.date.weekday() + 7 * (rows - 1)) as i64));
+    for i in 0..cols * rows {
+        let day = first.add_days(i as i64);
+        let cx = gx + (i % cols) as f32 * (cell + gap);
+        let cy = gy + 10.0 + (i / cols) as f32 * (cell + gap);
+        let future = day > challenge.date;
+        let played = history.best(day).is_some();
+        let bg = if played { theme.palette.ui_accent } else { theme.palette.ui_fg };
+        let alpha = if future { 0.3 } else { 1.0 };
+        draw_rounded_rect(cx, cy, cell, cell, 6.0, Color::new(bg.r, bg.g, bg.b, alpha));
+        if day == challenge.date {
+            draw_rectangle_lines(cx - 2.0, cy - 2.0, cell + 4.0, cell + 4.0, 2.0, WHITE);
+        }
+        let label = day.day.to_string();
+        let dims = measure_text(&label, None, 16, 1.0);
+        let tc = if played { WHITE } else { theme.palette.ui_text };
+        draw_text(&label, cx + cell / 2.0 - dims.width / 2.0, cy + cell / 2.0 + 5.0, 16.0, Color::new(tc.r, tc.g, tc.b, alpha));
+    }
+
+    let actions_y = gy + 10.0 + rows as f32 * (cell + gap) + 40.0;
+    for (i, action) in ["START", "BACK"].iter().enumerate() {
+        draw_menu_item(theme, action, sw / 2.0, actions_y + i as f32 * 55.0, i == selection, animation_time);
+    }
+
+    let hint = "Everyone plays the same city today (UTC). ENTER to select, ESC to go back";
+    let hint_dims = measure_text(hint, None, theme.font_size_small as u16, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// One line of an options screen (settings, match setup)
+pub struct OptionRow {
+    pub label: &'static str,
+    pub value: String,
+    /// Position of the slider knob (0-1), `None` for choices and toggles
+    pub slider: Option<f32>,
+}
+
+/// Draw the settings screen: one row per setting plus the trailing action rows
+pub fn draw_settings(theme: &Theme, settings: &Settings, selection: usize, animation_time: f32) {
+    let rows: Vec<OptionRow> = SettingId::ALL.iter().map(|id| OptionRow {
+        label: id.label(),
+        value: settings.display(*id),
+        slider: match id.kind() {
+            SettingKind::Slider { min, max, .. } => Some((settings.get(*id) - min) / (max - min)),
+            _ => None,
+        },
+    }).collect();
+    let hint = "UP/DOWN to select, LEFT/RIGHT to change, ESC to save and go back";
+    draw_options_screen(theme, "SETTINGS", &rows, &["RESET TO DEFAULTS", "BACK"], selection, hint, animation_time);
+}
+
+/// Draw the match setup screen shown before a round starts
+pub fn draw_lobby(theme: &Theme, setup: &MatchSetup, selection: usize, animation_time: f32) {
+    let rows: Vec<OptionRow> = LobbyField::rows(setup).iter().map(|field| OptionRow {
+        label: field.label(),
+        value: field.display(setup),
+        slider: field.slider(setup),
+    }).collect();
+    let title = format!("{} - MATCH SETUP", setup.rules.mode.name().to_uppercase());
+    let hint = "LEFT/RIGHT to change, type digits for the seed, ENTER to start, ESC to go back";
+    draw_options_screen(theme, &title, &rows, &["START", "BACK"], selection, hint, animation_time);
+}
+
+/// Draw the skin customization screen around a live preview of `skin`
+pub fn draw_customize(theme: &Theme, skin: HoleSkin, profile: &PlayerProfile, player_name: &str, selection: usize, animation_time: f32) {
+    let sw = screen_width();
+    let sh = screen_height();
+    draw_rectangle(0.0, 0.0, sw, sh, theme.palette.background);
+    draw_grid_background(theme, animation_time);
+
+    let title = "CUSTOMIZE";
+    let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
+    draw_text(title, sw / 2.0 - title_dims.width / 2.0, sh * 0.1, theme.font_size_large, theme.palette.ui_text);
+
+    // Preview hole at the center of the upper half, drawn like in game
+    let center = vec2(sw / 2.0, sh * 0.36);
+    let mut preview = Hole::new_player(0, 0.0, 0.0, player_name.to_string());
+    preview.radius = (sh * 0.13).min(90.0);
+    preview.pulse_timer = animation_time;
+    preview.set_skin(skin);
+    draw_hole(&preview, theme, -center.x, -center.y, 1.0);
+
+    let row_w = 520.0;
+    let row_h = 34.0;
+    let x = sw / 2.0 - row_w / 2.0;
+    let start_y = sh * 0.56;
+    let entries = [("Pattern", skin.pattern_def()), ("Border", skin.border_def())];
+    for (i, (label, def)) in entries.iter().enumerate() {
+        let y = start_y + i as f32 * (row_h + 4.0);
+        let value = def.map_or("?", |d| d.name).to_string();
+        draw_option_row(theme, &OptionRow { label, value, slider: None }, x, y, row_w, row_h, i == selection);
+    }
+
+    // Say what unlocks the previewed entries that are still locked
+    let locked: Vec<String> = entries.iter()
+        .filter_map(|(label, def)| def.filter(|d| !d.unlock.is_met(profile)).map(|d| format!("{} {}: {}", d.name, label.to_lowercase(), d.unlock.description())))
+        .collect();
+    for (i, txt) in locked.iter().enumerate() {
+        let txt = format!("LOCKED - {}", txt);
+        let dims = measure_text(&txt, None, theme.font_size_small as u16, 1.0);
+        draw_text(&txt, sw / 2.0 - dims.width / 2.0, start_y + 2.0 * (row_h + 4.0) + 22.0 + i as f32 * 22.0, theme.font_size_small, theme.palette.ui_accent);
+    }
+
+    draw_menu_item(theme, "BACK", sw / 2.0, start_y + 2.0 * (row_h + 4.0) + 90.0, selection == entries.len(), animation_time);
+
+    let hint = "LEFT/RIGHT to browse, locked skins can be previewed but not worn, ESC to go back";
+    let hint_dims = measure_text(hint, None, theme.font_size_small as u16, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// One label/value row of an options screen
+fn draw_option_row(theme: &Theme, row: &OptionRow, x: f32, y: f32, row_w: f32, row_h: f32, selected: bool) {
+    let bg = if selected { theme.palette.ui_accent } else { theme.palette.ui_fg };
+    draw_rounded_rect(x, y, row_w, row_h, theme.corner_radius, bg);
+    let tc = if selected { WHITE } else { theme.palette.ui_text };
+    draw_text(row.label, x + 15.0, y + 23.0, theme.font_size_medium * 0.8, tc);
+
+    // Slider track or value text on the right half
+    let vx = x + row_w * 0.5;
+    let vw = row_w * 0.5 - 15.0;
+    if let Some(t) = row.slider {
+        let t = t.clamp(0.0, 1.0);
+        let track_w = vw - 80.0;
+        draw_rectangle(vx, y + row_h / 2.0 - 3.0, track_w, 6.0, Color::new(0.0, 0.0, 0.0, 0.35));
+        draw_rectangle(vx, y + row_h / 2.0 - 3.0, track_w * t, 6.0, tc);
+        draw_circle(vx + track_w * t, y + row_h / 2.0, 7.0, tc);
+    }
+    let value = if row.slider.is_some() { row.value.clone() } else { format!("< {} >", row.value) };
+    let vdims = measure_text(&value, None, theme.font_size_small as u16, 1.0);
+    draw_text(&value, x + row_w - 15.0 - vdims.width, y + 22.0, theme.font_size_small, tc);
+}
+
+/// Rows of label/value pairs followed by centered action buttons; `selection` indexes rows then actions
+fn draw_options_screen(theme: &Theme, title: &str, rows: &[OptionRow], actions: &[&str], selection: usize, hint: &str, animation_time: f32) {
+    let sw = screen_width();
+    let sh = screen_height();
+    draw_rectangle(0.0, 0.0, sw, sh, theme.palette.background);
+    draw_grid_background(theme, animation_time);
+
+    let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
+    draw_text(title, sw / 2.0 - title_dims.width / 2.0, sh * 0.1, theme.font_size_large, theme.palette.ui_text);
+
+    let row_w = 520.0;
+    let row_h = 34.0;
+    let x = sw / 2.0 - row_w / 2.0;
+    let start_y = sh * 0.1 + 30.0;
+    for (i, row) in rows.iter().enumerate() {
+        let y = start_y + i as f32 * (row_h + 4.0);
+        draw_option_row(theme, row, x, y, row_w, row_h, i == selection);
+    }
+
+    let actions_y = start_y + rows.len() as f32 * (row_h + 4.0) + 35.0;
+    for (i, action) in actions.iter().enumerate() {
+        let selected = rows.len() + i == selection;
+        draw_menu_item(theme, action, sw / 2.0, actions_y + i as f32 * 55.0, selected, animation_time);
+    }
+
+    let hint_dims = measure_text(hint, None, theme.font_size_small as u16, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// Draw the HUD during gameplay over a view of `view` (width, height) pixels
+#[allow(clippy::too_many_arguments)]
+pub fn draw_hud(theme: &Theme, view: (f32, f32), timer: f32, leaderboard: &[LeaderboardEntry], player_rank: Option<usize>, player_size: f32, mode: GameMode, city_consumed: f32, dash_cooldown: f32, dash_cooldown_max: f32, zone: Option<&SafeZone>) {
+    let (sw, sh) = view;
 
     if mode.has_timer() { draw_timer(theme, sw / 2.0, 30.0, timer); }
+    if let Some(zone) = zone { draw_zone_countdown(theme, sw / 2.0, 30.0, zone); }
     draw_leaderboard(theme, sw - 20.0, 20.0, leaderboard, player_rank);
     draw_player_stats(theme, 20.0, sh - 80.0, player_size, player_rank, mode, city_consumed);
     draw_dash_indicator(theme, sw / 2.0, sh - 40.0, dash_cooldown, dash_cooldown_max);
 }
 
+/// Tag in the corner of a split-screen view saying whose it is, in their hole's color
+pub fn draw_view_label(theme: &Theme, slot: usize, name: &str) {
+    let text = format!("P{} {}", slot + 1, name);
+    let dims = measure_text(&text, None, theme.font_size_small as u16, 1.0);
+    draw_rounded_rect(12.0, 12.0, dims.width + 24.0, 30.0, theme.corner_radius, theme.palette.ui_bg);
+    draw_rectangle(12.0, 18.0, 4.0, 18.0, player_color(slot));
+    draw_text(&text, 24.0, 33.0, theme.font_size_small, theme.palette.ui_text);
+}
+
 fn draw_timer(theme: &Theme, x: f32, y: f32, time_remaining: f32) {
     let mins = (time_remaining / 60.0).floor() as i32;
     let secs = (time_remaining % 60.0).floor() as i32;
@@ -121,6 +349,18 @@ fn draw_timer(theme: &Theme, x: f32, y: f32, time_remaining: f32) {
     draw_text(&timer_text, x - text_dims.width / 2.0, y + text_dims.height, font_size, color);
 }
 
+fn draw_zone_countdown(theme: &Theme, x: f32, y: f32, zone: &SafeZone) {
+    if zone.is_closed() { return; }
+    let secs = zone.timer.max(0.0).ceil() as i32;
+    let label = if zone.shrinking { "ZONE CLOSING" } else { "ZONE SHRINKS IN" };
+    let text = format!("{} {}:{:02}", label, secs / 60, secs % 60);
+    let font_size = theme.font_size_medium;
+    let dims = measure_text(&text, None, font_size as u16, 1.0);
+    draw_rounded_rect(x - dims.width / 2.0 - 15.0, y - 5.0, dims.width + 30.0, dims.height + 20.0, theme.corner_radius, theme.palette.ui_bg);
+    let color = if zone.shrinking || secs <= 10 { Color::new(1.0, 0.3, 0.4, 1.0) } else { theme.palette.ui_text };
+    draw_text(&text, x - dims.width / 2.0, y + dims.height + 5.0, font_size, color);
+}
+
 fn draw_leaderboard(theme: &Theme, x: f32, y: f32, entries: &[LeaderboardEntry], _player_rank: Option<usize>) {
     let card_w = 200.0;
     let entry_h = 28.0;
@@ -172,54 +412,437 @@ pub fn draw_pause_overlay(theme: &Theme, selection: usize, animation_time: f32)
     let sh = screen_height();
     draw_rectangle(0.0, 0.0, sw, sh, Color::new(0.0, 0.0, 0.0, 0.7));
     let card_w = 300.0;
-    let card_h = 250.0;
+    let card_h = 300.0;
     draw_rounded_rect_shadow(sw / 2.0 - card_w / 2.0, sh / 2.0 - card_h / 2.0, card_w, card_h, theme.corner_radius * 2.0, theme.palette.ui_bg, Color::new(0.0, 0.0, 0.0, 0.5), 8.0);
     let title_dims = measure_text("PAUSED", None, theme.font_size_large as u16, 1.0);
     draw_text("PAUSED", sw / 2.0 - title_dims.width / 2.0, sh / 2.0 - card_h / 2.0 + 50.0, theme.font_size_large, theme.palette.ui_accent);
-    let options = ["RESUME", "RESTART", "EXIT"];
+    let options = ["RESUME", "RESTART", "SAVE & QUIT", "EXIT"];
     for (i, opt) in options.iter().enumerate() {
         draw_menu_item(theme, opt, sw / 2.0, sh / 2.0 - card_h / 2.0 + 100.0 + i as f32 * 45.0, i == selection, animation_time);
     }
 }
 
-/// Draw results screen
-pub fn draw_results(theme: &Theme, mode: GameMode, player_rank: usize, player_size: f32, total_players: usize, city_consumed: f32, selection: usize, animation_time: f32) {
+/// Draw results screen; with more than one human `standings` ranks them all
+#[allow(clippy::too_many_arguments)]
+pub fn draw_results(theme: &Theme, result: &VictoryResult, last_elimination: Option<&Elimination>, player_rank: usize, player_size: f32, total_players: usize, standings: &[Standing], daily_score: Option<(u32, bool)>, xp_gain: Option<&XpGain>, options: &[&str], selection: usize, animation_time: f32) {
     let sw = screen_width();
     let sh = screen_height();
     draw_rectangle(0.0, 0.0, sw, sh, Color::new(0.0, 0.0, 0.0, 0.8));
-    let card_w = 400.0;
-    let card_h = 350.0;
+    let card_w = 420.0;
+    let card_h = 480.0;
     let card_y = sh / 2.0 - card_h / 2.0;
     let entrance_t = (animation_time * 2.0).min(1.0);
     let animated_y = card_y + 50.0 * (1.0 - ease_out_back(entrance_t));
     draw_rounded_rect_shadow(sw / 2.0 - card_w / 2.0, animated_y, card_w, card_h, theme.corner_radius * 2.0, theme.palette.ui_bg, Color::new(0.0, 0.0, 0.0, 0.5), 8.0);
 
-    let title = if mode == GameMode::Solo { if city_consumed >= 100.0 { "PERFECT!" } else { "GAME OVER" } } else { if player_rank == 1 { "VICTORY!" } else { "GAME OVER" } };
-    let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
-    draw_text(title, sw / 2.0 - title_dims.width / 2.0, animated_y + 50.0, theme.font_size_large, theme.palette.ui_accent);
+    let split = standings.len() > 1;
+    let title = match result {
+        // Split screen names the human on top
+        VictoryResult::PlayerWon | VictoryResult::TimeUp { player_rank: 1, .. } if split => format!("{} WINS!", standings[0].name.to_uppercase()),
+        VictoryResult::PlayerWon | VictoryResult::TimeUp { player_rank: 1, .. } => "VICTORY!".to_string(),
+        VictoryResult::CityConsumed { percentage } if *percentage >= 100.0 => "PERFECT!".to_string(),
+        _ => "GAME OVER".to_string(),
+    };
+    let title_dims = measure_text(&title, None, theme.font_size_large as u16, 1.0);
+    draw_text(&title, sw / 2.0 - title_dims.width / 2.0, animated_y + 50.0, theme.font_size_large, theme.palette.ui_accent);
 
-    match mode {
-        GameMode::Solo => {
-            let txt = format!("City: {:.1}%", city_consumed);
-            let dims = measure_text(&txt, None, theme.font_size_medium as u16, 1.0);
-            draw_text(&txt, sw / 2.0 - dims.width / 2.0, animated_y + 100.0, theme.font_size_medium, theme.palette.ui_text);
+    let centered = |txt: &str, y: f32, size: f32, color: Color| {
+        let dims = measure_text(txt, None, size as u16, 1.0);
+        draw_text(txt, sw / 2.0 - dims.width / 2.0, y, size, color);
+    };
+
+    // How the round went for the player: eaten, who won, or the last time they were eaten
+    let detail = match result {
+        VictoryResult::PlayerEliminated { killer_name, time } => Some(elimination_text(killer_name.as_deref(), *time, false)),
+        VictoryResult::TimeUp { winner_name, player_rank } if *player_rank != 1 && !winner_name.is_empty() => Some(format!("Winner: {}", winner_name)),
+        _ => last_elimination.map(|e| elimination_text(e.killer_name.as_deref(), e.time, true)),
+    };
+
+    match result {
+        _ if split => draw_standings(theme, standings, total_players, sw / 2.0 - card_w / 2.0 + 30.0, animated_y + 80.0, card_w - 60.0),
+        VictoryResult::CityConsumed { percentage } => {
+            centered(&format!("City: {:.1}%", percentage), animated_y + 100.0, theme.font_size_medium, theme.palette.ui_text);
         }
         _ => {
-            let txt = format!("Rank: #{} / {}", player_rank, total_players);
-            let dims = measure_text(&txt, None, theme.font_size_medium as u16, 1.0);
-            draw_text(&txt, sw / 2.0 - dims.width / 2.0, animated_y + 100.0, theme.font_size_medium, theme.palette.ui_text);
-            let stxt = format!("Size: {:.0}", player_size);
-            let sdims = measure_text(&stxt, None, theme.font_size_medium as u16, 1.0);
-            draw_text(&stxt, sw / 2.0 - sdims.width / 2.0, animated_y + 140.0, theme.font_size_medium, theme.palette.ui_text);
+            centered(&format!("Rank: #{} / {}", player_rank, total_players), animated_y + 100.0, theme.font_size_medium, theme.palette.ui_text);
+            let line = match daily_score {
+                Some((score, true)) => format!("Daily score: {}  NEW BEST!", score),
+                Some((score, false)) => format!("Daily score: {}", score),
+                None => format!("Size: {:.0}", player_size),
+            };
+            centered(&line, animated_y + 140.0, theme.font_size_medium, theme.palette.ui_text);
         }
     }
+    if let Some(detail) = detail.filter(|_| !split) {
+        centered(&detail, animated_y + 180.0, theme.font_size_small, theme.palette.ui_text_secondary);
+    }
+    if let Some(gain) = xp_gain {
+        draw_xp_gain(theme, gain, sw / 2.0 - card_w / 2.0 + 40.0, animated_y + 205.0, card_w - 80.0, animation_time);
+    }
 
-    let options = ["PLAY AGAIN", "CHANGE MODE", "MAIN MENU"];
     for (i, opt) in options.iter().enumerate() {
-        draw_menu_item(theme, opt, sw / 2.0, animated_y + 200.0 + i as f32 * 45.0, i == selection, animation_time);
+        draw_menu_item(theme, opt, sw / 2.0, animated_y + 285.0 + i as f32 * 45.0, i == selection, animation_time);
+    }
+}
+
+/// Draw the connection screen shown while not in a LAN round
+pub fn draw_network_status(theme: &Theme, status: &ClientStatus, server: &str, animation_time: f32) {
+    let sw = screen_width();
+    let sh = screen_height();
+    clear_background(theme.palette.background);
+    let card_w = 420.0;
+    let card_h = 320.0;
+    let card_x = sw / 2.0 - card_w / 2.0;
+    let card_y = sh / 2.0 - card_h / 2.0;
+    draw_rounded_rect_shadow(card_x, card_y, card_w, card_h, theme.corner_radius * 2.0, theme.palette.ui_bg, Color::new(0.0, 0.0, 0.0, 0.5), 8.0);
+
+    let centered = |txt: &str, y: f32, size: f32, color: Color| {
+        let dims = measure_text(txt, None, size as u16, 1.0);
+        draw_text(txt, sw / 2.0 - dims.width / 2.0, y, size, color);
+    };
+    centered("LAN GAME", card_y + 50.0, theme.font_size_large, theme.palette.ui_accent);
+    centered(server, card_y + 85.0, theme.font_size_small, theme.palette.ui_text_secondary);
+
+    let dots = ".".repeat((animation_time * 2.0) as usize % 4);
+    match status {
+        ClientStatus::Connecting => centered(&format!("Connecting{}", dots), card_y + 140.0, theme.font_size_medium, theme.palette.ui_text),
+        ClientStatus::Lobby { players, needed } => {
+            let line = if players.len() < *needed {
+                format!("Waiting for players ({} / {}){}", players.len(), needed, dots)
+            } else {
+                format!("Waiting for the next round{}", dots)
+            };
+            centered(&line, card_y + 130.0, theme.font_size_medium, theme.palette.ui_text);
+            for (i, name) in players.iter().take(6).enumerate() {
+                centered(name, card_y + 165.0 + i as f32 * 22.0, theme.font_size_small, player_color(i));
+            }
+        }
+        ClientStatus::Playing => centered(&format!("Starting{}", dots), card_y + 140.0, theme.font_size_medium, theme.palette.ui_text),
+        ClientStatus::Refused(reason) => centered(&format!("Refused: {}", reason), card_y + 140.0, theme.font_size_medium, theme.palette.ui_text),
+        ClientStatus::TimedOut => centered("The server stopped answering", card_y + 140.0, theme.font_size_medium, theme.palette.ui_text),
+    }
+    centered("Esc to leave", card_y + card_h - 25.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// Every human's place, size and eliminations, best first
+fn draw_standings(theme: &Theme, standings: &[Standing], total_players: usize, x: f32, y: f32, w: f32) {
+    let row_h = 28.0;
+    for (i, s) in standings.iter().enumerate() {
+        let ry = y + i as f32 * row_h;
+        draw_rounded_rect(x, ry, w, row_h - 4.0, theme.corner_radius, theme.palette.ui_fg);
+        draw_rectangle(x, ry + 4.0, 4.0, row_h - 12.0, player_color(s.slot));
+        let tc = if i == 0 { theme.palette.ui_accent } else { theme.palette.ui_text };
+        draw_text(&format!("#{} / {}", s.rank, total_players), x + 12.0, ry + 18.0, theme.font_size_small, tc);
+        draw_text(&format!("P{} {}", s.slot + 1, s.name), x + 90.0, ry + 18.0, theme.font_size_small, tc);
+        let stats = format!("{:.0}  {} KO", s.size, s.eliminations);
+        let dims = measure_text(&stats, None, theme.font_size_small as u16, 1.0);
+        draw_text(&stats, x + w - 10.0 - dims.width, ry + 18.0, theme.font_size_small, theme.palette.ui_text_secondary);
     }
 }
 
+/// XP counting up into the level bar once the results card has settled
+fn draw_xp_gain(theme: &Theme, gain: &XpGain, x: f32, y: f32, w: f32, animation_time: f32) {
+    let t = ((animation_time - 0.6) / 1.5).clamp(0.0, 1.0);
+    let eased = 1.0 - (1.0 - t).powi(3);
+    let shown = gain.previous_xp + (gain.gained as f32 * eased) as u64;
+    let level = level_for_xp(shown);
+
+    draw_text(&format!("Level {}", level), x, y + 14.0, theme.font_size_small, theme.palette.ui_text);
+    let xp_text = format!("+{} XP", (gain.gained as f32 * eased) as u32);
+    let xp_dims = measure_text(&xp_text, None, theme.font_size_small as u16, 1.0);
+    draw_text(&xp_text, x + w - xp_dims.width, y + 14.0, theme.font_size_small, theme.palette.ui_accent);
+
+    draw_rounded_rect(x, y + 24.0, w, 10.0, 4.0, theme.palette.ui_fg);
+    let progress = level_progress(shown);
+    if progress > 0.0 { draw_rounded_rect(x, y + 24.0, w * progress, 10.0, 4.0, theme.palette.ui_accent); }
+
+    if level > level_for_xp(gain.previous_xp) && (animation_time * 4.0).sin() > -0.5 {
+        let txt = "LEVEL UP!";
+        let dims = measure_text(txt, None, theme.font_size_small as u16, 1.0);
+        draw_text(txt, x + w / 2.0 - dims.width / 2.0, y + 14.0, theme.font_size_small, theme.palette.ui_accent);
+    }
+}
+
+/// Draw the profile page: level, XP bar and lifetime stats
+pub fn draw_profile(theme: &Theme, profile: &PlayerProfile, player_name: &str, selection: usize, animation_time: f32) {
+    let sw = screen_width();
+    let sh = screen_height();
+    draw_rectangle(0.0, 0.0, sw, sh, theme.palette.background);
+    draw_grid_background(theme, animation_time);
+
+    let title = "PROFILE";
+    let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
+    draw_text(title, sw / 2.0 - title_dims.width / 2.0, sh * 0.1, theme.font_size_large, theme.palette.ui_text);
+
+    let row_w = 520.0;
+    let row_h = 34.0;
+    let x = sw / 2.0 - row_w / 2.0;
+    let mut y = sh * 0.1 + 30.0;
+
+    // Level and progress toward the next one
+    let level = profile.level();
+    draw_text(&format!("Level {}", level), x, y + 22.0, theme.font_size_medium, theme.palette.ui_accent);
+    let next = xp_for_level(level + 1);
+    let xp_text = format!("{} / {} XP", profile.total_xp, next);
+    let xp_dims = measure_text(&xp_text, None, theme.font_size_small as u16, 1.0);
+    draw_text(&xp_text, x + row_w - xp_dims.width, y + 22.0, theme.font_size_small, theme.palette.ui_text);
+    y += 32.0;
+    draw_rounded_rect(x, y, row_w, 10.0, 4.0, theme.palette.ui_fg);
+    let progress = level_progress(profile.total_xp);
+    if progress > 0.0 { draw_rounded_rect(x, y, row_w * progress, 10.0, 4.0, theme.palette.ui_accent); }
+    y += 24.0;
+
+    let mode_row = |mode: GameMode| {
+        let stats = profile.mode_stats(mode);
+        (mode.name(), format!("{} played / {} won", stats.matches, stats.wins))
+    };
+    // The medal strings start with an emoji the default font cannot draw
+    let medal = profile.solo_medal().trim_start_matches(|c: char| !c.is_ascii()).trim_start();
+    let rows = [
+        ("Name", player_name.to_string()),
+        ("Matches", format!("{} played / {} won", profile.matches_played(), profile.wins())),
+        mode_row(GameMode::Classic),
+        mode_row(GameMode::Battle),
+        mode_row(GameMode::Solo),
+        ("Best Size", format!("{:.0}", profile.best_size)),
+        ("Objects Eaten", profile.objects_eaten.to_string()),
+        ("Eliminations", profile.eliminations.to_string()),
+        ("Best Solo", format!("{:.1}% - {}", profile.best_solo_percentage, medal)),
+        ("Achievements", format!("{} / {}", profile.achievements.len(), Achievement::ALL.len())),
+    ];
+    for (label, value) in &rows {
+        draw_rounded_rect(x, y, row_w, row_h, theme.corner_radius, theme.palette.ui_fg);
+        draw_text(label, x + 15.0, y + 23.0, theme.font_size_medium * 0.8, theme.palette.ui_text);
+        let vdims = measure_text(value, None, theme.font_size_small as u16, 1.0);
+        draw_text(value, x + row_w - 15.0 - vdims.width, y + 22.0, theme.font_size_small, theme.palette.ui_text);
+        y += row_h + 4.0;
+    }
+
+    draw_menu_item(theme, "EDIT NAME", sw / 2.0 - 130.0, y + 35.0, selection == 0, animation_time);
+    draw_menu_item(theme, "BACK", sw / 2.0 + 130.0, y + 35.0, selection == 1, animation_time);
+
+    let hint = "LEFT/RIGHT to select, ENTER to confirm, ESC to go back";
+    let hint_dims = measure_text(hint, None, theme.font_size_small as u16, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// Draw the high score screen: the filter rows, then the matching results
+pub fn draw_high_scores(theme: &Theme, scores: &HighScores, filter: &HighScoreFilter, selection: usize, animation_time: f32) {
+    let sw = screen_width();
+    let sh = screen_height();
+    draw_rectangle(0.0, 0.0, sw, sh, theme.palette.background);
+    draw_grid_background(theme, animation_time);
+
+    let title = "HIGH SCORES";
+    let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
+    draw_text(title, sw / 2.0 - title_dims.width / 2.0, sh * 0.1, theme.font_size_large, theme.palette.ui_text);
+
+    let row_w = 520.0;
+    let row_h = 34.0;
+    let x = sw / 2.0 - row_w / 2.0;
+    let mut y = sh * 0.1 + 30.0;
+    for (i, field) in HighScoreField::ALL.iter().enumerate() {
+        let row = OptionRow { label: field.label(), value: field.display(filter), slider: None };
+        draw_option_row(theme, &row, x, y, row_w, row_h, i == selection);
+        y += row_h + 4.0;
+    }
+
+    // Results table; the seed column only matters when seeds are merged
+    let table_w = 760.0;
+    let entry_h = 26.0;
+    let tx = sw / 2.0 - table_w / 2.0;
+    let top = scores.top(filter);
+    let rows = top.len().max(1);
+    y += 10.0;
+    draw_rounded_rect(tx, y, table_w, 36.0 + rows as f32 * entry_h + 8.0, theme.corner_radius, theme.palette.ui_bg);
+    let mut columns = vec![("#", 10.0), ("NAME", 45.0), ("SIZE", 225.0), ("RANK", 295.0), ("ELIMS", 365.0), ("EATEN", 440.0), ("CITY", 520.0), ("DATE", 600.0)];
+    if filter.seed.is_none() {
+        columns.push(("SEED", 710.0));
+    }
+    for (label, cx) in &columns {
+        draw_text(label, tx + cx, y + 26.0, theme.font_size_small, theme.palette.ui_accent);
+    }
+    y += 36.0;
+    if top.is_empty() {
+        draw_text("No results yet", tx + 45.0, y + 18.0, theme.font_size_small, theme.palette.ui_text_secondary);
+    }
+    for (i, (seed, entry)) in top.iter().enumerate() {
+        let ey = y + i as f32 * entry_h + 18.0;
+        let name: String = entry.name.chars().take(12).collect();
+        let mut cells = vec![
+            format!("{}.", i + 1),
+            name,
+            format!("{:.0}", entry.size),
+            format!("#{}", entry.rank),
+            entry.eliminations.to_string(),
+            entry.score.to_string(),
+            format!("{:.1}%", entry.city_consumed),
+            entry.date.to_string(),
+        ];
+        if filter.seed.is_none() {
+            // Long seeds are cut, the full one is on the seed filter
+            let seed = seed.to_string();
+            cells.push(if seed.len() > 6 { format!("{}..", &seed[..4]) } else { seed });
+        }
+        for (cell, (_, cx)) in cells.iter().zip(&columns) {
+            draw_text(cell, tx + cx, ey, theme.font_size_small, theme.palette.ui_text);
+        }
+    }
+    y += rows as f32 * entry_h + 8.0;
+
+    draw_menu_item(theme, "BACK", sw / 2.0, y + 40.0, selection == HighScoreField::ALL.len(), animation_time);
+
+    let hint = "UP/DOWN to select, LEFT/RIGHT to filter, ESC to go back";
+    let hint_dims = measure_text(hint, None, theme.font_size_small as u16, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// Draw the name entry screen; `first_run` for the prompt shown before the first menu
+pub fn draw_name_entry(theme: &Theme, input: &TextInput, keyboard: Option<&OnScreenKeyboard>, first_run: bool, animation_time: f32) {
+    let sw = screen_width();
+    let sh = screen_height();
+    draw_rectangle(0.0, 0.0, sw, sh, theme.palette.background);
+    draw_grid_background(theme, animation_time);
+
+    let title = if first_run { "WELCOME" } else { "PLAYER NAME" };
+    let title_dims = measure_text(title, None, theme.font_size_large as u16, 1.0);
+    draw_text(title, sw / 2.0 - title_dims.width / 2.0, sh * 0.15, theme.font_size_large, theme.palette.ui_text);
+    let prompt = "What should your hole be called?";
+    let prompt_dims = measure_text(prompt, None, theme.font_size_medium as u16, 1.0);
+    draw_text(prompt, sw / 2.0 - prompt_dims.width / 2.0, sh * 0.15 + 45.0, theme.font_size_medium, theme.palette.ui_text_secondary);
+
+    let field_w = 420.0;
+    let field_y = sh * 0.15 + 75.0;
+    draw_text_input(theme, input, sw / 2.0 - field_w / 2.0, field_y, field_w, 50.0, keyboard.is_none(), animation_time);
+    let count = format!("{} / {}", input.len(), MAX_NAME_LEN);
+    let count_dims = measure_text(&count, None, theme.font_size_small as u16, 1.0);
+    draw_text(&count, sw / 2.0 + field_w / 2.0 - count_dims.width, field_y + 72.0, theme.font_size_small, theme.palette.ui_text_secondary);
+
+    if let Some(osk) = keyboard {
+        draw_on_screen_keyboard(theme, osk, sw / 2.0, field_y + 110.0, 48.0);
+    }
+
+    let hint = if keyboard.is_some() {
+        "ARROWS to pick a key, ENTER to press it, ESC to hide the keyboard"
+    } else if input.value().is_none() {
+        "Type a name (letters, digits, space - _ .), TAB / Y for the on-screen keyboard"
+    } else if first_run {
+        "ENTER to confirm, TAB / Y for the on-screen keyboard, ESC to keep \"Player\""
+    } else {
+        "ENTER to confirm, TAB / Y for the on-screen keyboard, ESC to cancel"
+    };
+    let hint_dims = measure_text(hint, None, theme.font_size_small as u16, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, sh - 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
+}
+
+/// Text field with a blinking caret; the caret only shows while the field has focus
+#[allow(clippy::too_many_arguments)]
+pub fn draw_text_input(theme: &Theme, input: &TextInput, x: f32, y: f32, w: f32, h: f32, focused: bool, animation_time: f32) {
+    let border = if focused { theme.palette.ui_accent } else { theme.palette.ui_fg };
+    draw_rounded_rect(x - 2.0, y - 2.0, w + 4.0, h + 4.0, theme.corner_radius, border);
+    draw_rounded_rect(x, y, w, h, theme.corner_radius, theme.palette.ui_bg);
+
+    let font_size = theme.font_size_medium;
+    let text_y = y + h / 2.0 + font_size / 4.0;
+    draw_text(input.text(), x + 15.0, text_y, font_size, theme.palette.ui_text);
+    if focused && (animation_time * 2.0).fract() < 0.5 {
+        let before: String = input.text().chars().take(input.caret()).collect();
+        let caret_x = x + 15.0 + measure_text(&before, None, font_size as u16, 1.0).width + 1.0;
+        draw_line(caret_x, y + 10.0, caret_x, y + h - 10.0, 2.0, theme.palette.ui_accent);
+    }
+}
+
+/// On-screen keyboard centered on `cx`, rows going down from `y`
+pub fn draw_on_screen_keyboard(theme: &Theme, osk: &OnScreenKeyboard, cx: f32, y: f32, key_size: f32) {
+    let gap = 6.0;
+    for row in 0..OnScreenKeyboard::rows() {
+        let keys = OnScreenKeyboard::row_keys(row);
+        // Character keys are square, the special keys in the last row are wider
+        let key_w = if row + 1 == OnScreenKeyboard::rows() { key_size * 2.5 } else { key_size };
+        let row_w = keys.len() as f32 * (key_w + gap) - gap;
+        let ky = y + row as f32 * (key_size + gap);
+        for (col, key) in keys.iter().enumerate() {
+            let kx = cx - row_w / 2.0 + col as f32 * (key_w + gap);
+            let selected = osk.row == row && osk.col == col;
+            let active = *key == OskKey::Shift && osk.shift;
+            let bg = if selected { theme.palette.ui_accent } else if active { theme.palette.ui_fg } else { theme.palette.ui_bg };
+            draw_rounded_rect(kx, ky, key_w, key_size, theme.corner_radius * 0.5, bg);
+            let label = key.label(osk.shift);
+            let dims = measure_text(&label, None, theme.font_size_small as u16, 1.0);
+            let tc = if selected { WHITE } else { theme.palette.ui_text };
+            draw_text(&label, kx + key_w / 2.0 - dims.width / 2.0, ky + key_size / 2.0 + dims.height / 2.0, theme.font_size_small, tc);
+        }
+    }
+}
+
+/// "Eaten by Blaze at 01:12", or the zone when no hole was involved; `last` for a round that went on
+fn elimination_text(killer_name: Option<&str>, time: f32, last: bool) -> String {
+    let secs = time as u32;
+    let cause = match (killer_name, last) {
+        (Some(name), false) => format!("Eaten by {}", name),
+        (Some(name), true) => format!("Last eaten by {}", name),
+        (None, false) => "Lost to the zone".to_string(),
+        (None, true) => "Last lost to the zone".to_string(),
+    };
+    format!("{} at {:02}:{:02}", cause, secs / 60, secs % 60)
+}
+
+/// Screen rect of the replay timeline bar (also used for mouse scrubbing)
+pub fn replay_timeline_rect() -> Rect {
+    let sw = screen_width();
+    let sh = screen_height();
+    Rect::new(sw * 0.15, sh - 50.0, sw * 0.7, 10.0)
+}
+
+/// Draw replay viewer controls: timeline, speed, followed hole
+#[allow(clippy::too_many_arguments)]
+pub fn draw_replay_overlay(theme: &Theme, tick: u64, total_ticks: u64, tick_rate: u32, speed: f32, paused: bool, followed: Option<&str>, animation_time: f32) {
+    let sw = screen_width();
+    let bar = replay_timeline_rect();
+    draw_rounded_rect(bar.x - 20.0, bar.y - 45.0, bar.w + 40.0, 80.0, theme.corner_radius, theme.palette.ui_bg);
+
+    // Timeline
+    let progress = if total_ticks > 0 { (tick as f32 / total_ticks as f32).min(1.0) } else { 0.0 };
+    draw_rounded_rect(bar.x, bar.y, bar.w, bar.h, 4.0, theme.palette.ui_fg);
+    if progress > 0.0 { draw_rounded_rect(bar.x, bar.y, bar.w * progress, bar.h, 4.0, theme.palette.ui_accent); }
+    draw_circle(bar.x + bar.w * progress, bar.y + bar.h / 2.0, 8.0, WHITE);
+
+    // Time and state
+    let fmt_time = |t: u64| { let secs = t / tick_rate as u64; format!("{:02}:{:02}", secs / 60, secs % 60) };
+    let time_text = format!("{} / {}", fmt_time(tick), fmt_time(total_ticks));
+    draw_text(&time_text, bar.x, bar.y - 15.0, theme.font_size_small, theme.palette.ui_text);
+    let blink = (animation_time * 3.0).sin() > 0.0;
+    let state_text = if paused { if blink { "PAUSED".to_string() } else { String::new() } } else { format!("{}x", speed) };
+    let state_dims = measure_text(&state_text, None, theme.font_size_small as u16, 1.0);
+    draw_text(&state_text, sw / 2.0 - state_dims.width / 2.0, bar.y - 15.0, theme.font_size_small, theme.palette.ui_accent);
+    let follow_text = match followed { Some(name) => format!("Following: {}", name), None => "Free camera".to_string() };
+    let follow_dims = measure_text(&follow_text, None, theme.font_size_small as u16, 1.0);
+    draw_text(&follow_text, bar.x + bar.w - follow_dims.width, bar.y - 15.0, theme.font_size_small, theme.palette.ui_text);
+
+    let hint = "SPACE pause  -/+ speed  LEFT/RIGHT seek  . step  TAB follow next  F free camera  ESC exit";
+    let hint_dims = measure_text(hint, None, 14, 1.0);
+    draw_text(hint, sw / 2.0 - hint_dims.width / 2.0, bar.y + 28.0, 14.0, theme.palette.ui_text_secondary);
+
+    let title = "REPLAY";
+    draw_text(title, 20.0, 40.0, theme.font_size_medium, theme.palette.ui_accent);
+}
+
+/// Draw an achievement unlock sliding down from the top of the screen
+pub fn draw_achievement_toast(theme: &Theme, name: &str, description: &str, age: f32, duration: f32) {
+    let sw = screen_width();
+    let w = 360.0;
+    let h = 64.0;
+    // Slide in, hold, slide out
+    let t = (age / 0.3).min(1.0).min((duration - age) / 0.3).max(0.0);
+    let y = -h + (h + 80.0) * ease_out_back(t);
+    let x = sw / 2.0 - w / 2.0;
+    draw_rounded_rect_shadow(x, y, w, h, theme.corner_radius, theme.palette.ui_bg, Color::new(0.0, 0.0, 0.0, 0.5), 6.0);
+    draw_rectangle(x, y + 8.0, 4.0, h - 16.0, theme.palette.ui_accent);
+    draw_text("ACHIEVEMENT UNLOCKED", x + 18.0, y + 20.0, 14.0, theme.palette.ui_accent);
+    draw_text(name, x + 18.0, y + 40.0, theme.font_size_medium * 0.8, theme.palette.ui_text);
+    draw_text(description, x + 18.0, y + 56.0, 14.0, theme.palette.ui_text_secondary);
+}
+
 /// Draw FPS counter
 pub fn draw_fps(theme: &Theme) {
     draw_text(&format!("FPS: {}", get_fps()), 10.0, 20.0, theme.font_size_small, theme.palette.ui_text_secondary);
@@ -3,6 +3,7 @@
 use macroquad::prelude::*;
 use crate::world::gen::{World, Street, Block};
 use crate::world::objects::{WorldObject, ObjectType, ObjectState};
+use crate::gameplay::zone::SafeZone;
 use crate::render::theme::{Theme, draw_rounded_rect};
 
 /// Draw the entire world
@@ -223,7 +224,7 @@ fn draw_object(obj: &WorldObject, theme: &Theme, camera_x: f32, camera_y: f32, z
 }
 
 /// Draw world bounds indicator
-pub fn draw_world_bounds(world: &World, theme: &Theme, camera_x: f32, camera_y: f32, zoom: f32) {
+pub fn draw_world_bounds(world: &World, _theme: &Theme, camera_x: f32, camera_y: f32, zoom: f32) {
     let x = -camera_x * zoom;
     let y = -camera_y * zoom;
     let w = world.width * zoom;
@@ -237,3 +238,37 @@ pub fn draw_world_bounds(world: &World, theme: &Theme, camera_x: f32, camera_y:
     draw_line(x, y, x, y + h, thickness, border_color);
     draw_line(x + w, y, x + w, y + h, thickness, border_color);
 }
+
+/// Draw the battle safe zone: the storm outside the edge and the next circle as a dashed line
+pub fn draw_safe_zone(zone: &SafeZone, _theme: &Theme, camera_x: f32, camera_y: f32, zoom: f32) {
+    const SEGMENTS: usize = 96;
+    let cx = (zone.center.x - camera_x) * zoom;
+    let cy = (zone.center.y - camera_y) * zoom;
+    let r = zone.radius * zoom;
+    // Far enough to cover the screen from anywhere inside the zone
+    let outer = r + (screen_width() + screen_height()) * 2.0;
+
+    let storm = Color::new(0.8, 0.1, 0.3, 0.25);
+    let edge = Color::new(1.0, 0.2, 0.4, 0.9);
+    for i in 0..SEGMENTS {
+        let a0 = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let a1 = (i + 1) as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let (d0, d1) = (Vec2::from_angle(a0), Vec2::from_angle(a1));
+        let c = vec2(cx, cy);
+        draw_triangle(c + d0 * r, c + d1 * r, c + d0 * outer, storm);
+        draw_triangle(c + d1 * r, c + d1 * outer, c + d0 * outer, storm);
+        draw_line(c.x + d0.x * r, c.y + d0.y * r, c.x + d1.x * r, c.y + d1.y * r, 4.0, edge);
+    }
+
+    // Next circle, every other segment
+    if zone.target_radius < zone.radius {
+        let tc = vec2((zone.target_center.x - camera_x) * zoom, (zone.target_center.y - camera_y) * zoom);
+        let tr = zone.target_radius * zoom;
+        for i in (0..SEGMENTS).step_by(2) {
+            let d0 = Vec2::from_angle(i as f32 / SEGMENTS as f32 * std::f32::consts::TAU);
+            let d1 = Vec2::from_angle((i + 1) as f32 / SEGMENTS as f32 * std::f32::consts::TAU);
+            let (p0, p1) = (tc + d0 * tr, tc + d1 * tr);
+            draw_line(p0.x, p0.y, p1.x, p1.y, 2.0, Color::new(1.0, 1.0, 1.0, 0.7));
+        }
+    }
+}
@@ -3,3 +3,5 @@ pub mod draw_world;
 pub mod draw_holes;
 pub mod draw_ui;
 pub mod vfx;
+pub mod camera;
+pub mod viewport;
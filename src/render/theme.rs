@@ -130,6 +130,7 @@ pub fn draw_rounded_rect(x: f32, y: f32, w: f32, h: f32, radius: f32, color: Col
 }
 
 /// Draw a rounded rectangle with shadow
+#[allow(clippy::too_many_arguments)]
 pub fn draw_rounded_rect_shadow(
     x: f32, y: f32, w: f32, h: f32, 
     radius: f32, color: Color, shadow_color: Color, shadow_offset: f32
@@ -6,6 +6,7 @@ use ::rand::rngs::StdRng;
 use ::rand::SeedableRng;
 
 /// VFX event types
+#[derive(Clone)]
 pub enum VfxType {
     SwallowParticles { x: f32, y: f32, color: Color, count: usize },
     Ripple { x: f32, y: f32, radius: f32, color: Color },
@@ -45,31 +46,42 @@ pub struct VfxSystem {
     ripples: Vec<Ripple>,
     screen_shake: f32,
     shake_intensity: f32,
+    /// Fraction of particles actually spawned (0-1)
+    particle_density: f32,
     rng: StdRng,
 }
 
 impl Default for VfxSystem {
     fn default() -> Self {
-        Self::new()
+        Self::new(0)
     }
 }
 
 impl VfxSystem {
-    pub fn new() -> Self {
+    /// Create a VFX system; particles draw from their own stream seeded per session
+    pub fn new(seed: u64) -> Self {
         Self {
             particles: Vec::with_capacity(500),
             ripples: Vec::with_capacity(20),
             screen_shake: 0.0,
             shake_intensity: 0.5,
-            rng: StdRng::from_entropy(),
+            particle_density: 1.0,
+            rng: StdRng::seed_from_u64(seed),
         }
     }
 
+    /// Apply the player's effect settings (both 0-1)
+    pub fn configure(&mut self, particle_density: f32, shake_intensity: f32) {
+        self.particle_density = particle_density.clamp(0.0, 1.0);
+        self.shake_intensity = shake_intensity.clamp(0.0, 1.0);
+    }
+
     /// Spawn a VFX event
     pub fn spawn(&mut self, vfx: VfxType) {
         match vfx {
             VfxType::SwallowParticles { x, y, color, count } => {
-                for _ in 0..count.min(30) {
+                let spawned = (count.min(30) as f32 * self.particle_density).round() as usize;
+                for _ in 0..spawned {
                     let angle = self.rng.gen::<f32>() * std::f32::consts::TAU;
                     let speed = self.rng.gen_range(50.0..150.0);
                     self.particles.push(Particle {
@@ -99,6 +111,7 @@ impl VfxSystem {
                 });
             }
             VfxType::Trail { x, y, color } => {
+                if self.rng.gen::<f32>() >= self.particle_density { return; }
                 self.particles.push(Particle {
                     x, y,
                     vx: 0.0,
@@ -0,0 +1,56 @@
+//! Split-screen viewports - one screen region per local player, world drawing clipped to it
+
+use macroquad::prelude::*;
+
+/// Screen regions for `count` players: side by side for two, two on top and a wide one
+/// below for three, a 2x2 grid for four. One player (or none) gets the whole screen.
+pub fn split_viewports(count: usize, sw: f32, sh: f32) -> Vec<Rect> {
+    let (hw, hh) = (sw / 2.0, sh / 2.0);
+    match count {
+        0 | 1 => vec![Rect::new(0.0, 0.0, sw, sh)],
+        2 => vec![Rect::new(0.0, 0.0, hw, sh), Rect::new(hw, 0.0, hw, sh)],
+        3 => vec![Rect::new(0.0, 0.0, hw, hh), Rect::new(hw, 0.0, hw, hh), Rect::new(0.0, hh, sw, hh)],
+        _ => vec![
+            Rect::new(0.0, 0.0, hw, hh),
+            Rect::new(hw, 0.0, hw, hh),
+            Rect::new(0.0, hh, hw, hh),
+            Rect::new(hw, hh, hw, hh),
+        ],
+    }
+}
+
+/// Draw into `rect` only: until `end`, (0, 0) is its top-left corner and nothing spills outside it
+pub fn begin(rect: Rect) {
+    // The GL viewport is in physical pixels measured from the bottom of the window
+    let dpi = screen_dpi_scale();
+    let bottom = screen_height() - (rect.y + rect.h);
+    set_camera(&Camera2D {
+        target: vec2(rect.w / 2.0, rect.h / 2.0),
+        zoom: vec2(2.0 / rect.w, 2.0 / rect.h),
+        viewport: Some((
+            (rect.x * dpi) as i32,
+            (bottom * dpi) as i32,
+            (rect.w * dpi) as i32,
+            (rect.h * dpi) as i32,
+        )),
+        ..Default::default()
+    });
+}
+
+/// Back to drawing on the whole screen
+pub fn end() {
+    set_default_camera();
+}
+
+/// Lines between the split-screen views
+pub fn draw_dividers(views: &[Rect], color: Color) {
+    let sw = screen_width();
+    for view in views {
+        if view.x > 0.0 {
+            draw_line(view.x, view.y, view.x, view.y + view.h, 4.0, color);
+        }
+        if view.y > 0.0 {
+            draw_line(0.0, view.y, sw, view.y, 4.0, color);
+        }
+    }
+}
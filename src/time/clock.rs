@@ -1,6 +1,9 @@
 //! Game timer with pause support
 
+use serde::{Deserialize, Serialize};
+
 /// Game clock with countdown
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GameClock {
     /// Total duration in seconds
     pub duration: f32,
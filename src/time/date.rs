@@ -0,0 +1,100 @@
+//! Calendar dates (UTC) - enough of a calendar for daily challenges, no timezone handling
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+/// A day of the proleptic Gregorian calendar
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Today in UTC, so everybody gets the same date at the same moment
+    pub fn today_utc() -> Self {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        Self::from_days((secs / 86_400) as i64)
+    }
+
+    /// Date from a day count since 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        // Civil-from-days, counting from 0000-03-01 so leap days fall at the end of a year
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Self { year, month, day }
+    }
+
+    /// Day count since 1970-01-01
+    pub fn to_days(&self) -> i64 {
+        let year = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let mp = (self.month as i64 + 9) % 12;
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The date `n` days later (earlier when negative)
+    pub fn add_days(&self, n: i64) -> Self {
+        Self::from_days(self.to_days() + n)
+    }
+
+    /// Day of the week, 0 = Monday
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.to_days() + 3).rem_euclid(7) as u32
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_counts_round_trip_across_leap_days_and_new_year() {
+        assert_eq!(Date::from_days(0), Date { year: 1970, month: 1, day: 1 });
+        let leap_day = Date { year: 2024, month: 2, day: 29 };
+        assert_eq!(leap_day.add_days(-1), Date { year: 2024, month: 2, day: 28 });
+        assert_eq!(leap_day.add_days(1), Date { year: 2024, month: 3, day: 1 });
+        // 1900 is not a leap year, 2000 is
+        assert_eq!(Date { year: 1900, month: 2, day: 28 }.add_days(1), Date { year: 1900, month: 3, day: 1 });
+        assert_eq!(Date { year: 2000, month: 2, day: 28 }.add_days(1), Date { year: 2000, month: 2, day: 29 });
+        assert_eq!(Date { year: 2025, month: 12, day: 31 }.add_days(1), Date { year: 2026, month: 1, day: 1 });
+        assert_eq!(Date { year: 1969, month: 12, day: 31 }.to_days(), -1);
+
+        let start = Date { year: 1999, month: 12, day: 1 }.to_days();
+        let mut previous = Date::from_days(start - 1);
+        for days in start..start + 3 * 366 {
+            let date = Date::from_days(days);
+            assert_eq!(date.to_days(), days);
+            assert!(date > previous, "{} after {}", date, previous);
+            previous = date;
+        }
+    }
+
+    #[test]
+    fn weekdays_and_display() {
+        // 2026-03-14 is a Saturday, 2000-01-01 one too
+        assert_eq!(Date { year: 2026, month: 3, day: 14 }.weekday(), 5);
+        assert_eq!(Date { year: 2000, month: 1, day: 1 }.weekday(), 5);
+        assert_eq!(Date::from_days(0).weekday(), 3);
+        assert_eq!(Date { year: 2024, month: 2, day: 9 }.to_string(), "2024-02-09");
+    }
+}
@@ -1 +1,3 @@
 pub mod clock;
+pub mod date;
+pub mod tick;
@@ -0,0 +1,54 @@
+//! Fixed-timestep accumulator - decouples simulation ticks from frame rate
+
+/// Simulation ticks per second
+pub const TICK_RATE: u32 = 60;
+/// Duration of one simulation tick in seconds
+pub const TICK_DT: f32 = 1.0 / TICK_RATE as f32;
+/// Upper bound on ticks run in a single frame (avoids a spiral of death after a hitch)
+pub const MAX_TICKS_PER_FRAME: u32 = 8;
+
+/// Accumulates variable frame time and hands out whole fixed ticks
+#[derive(Clone)]
+pub struct FixedTimestep {
+    accumulator: f32,
+    /// Seconds per tick
+    step: f32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FixedTimestep {
+    /// Ticks of `TICK_DT`, the rate every local round runs at
+    pub fn new() -> Self {
+        Self::with_rate(TICK_RATE)
+    }
+
+    /// Ticks of `1 / rate` seconds (a dedicated server may run at another rate)
+    pub fn with_rate(rate: u32) -> Self {
+        Self { accumulator: 0.0, step: 1.0 / rate.max(1) as f32 }
+    }
+
+    /// Seconds per tick
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Add a frame's delta, returns how many ticks should run this frame
+    pub fn advance(&mut self, frame_dt: f32) -> u32 {
+        self.accumulator += frame_dt.max(0.0);
+        let mut ticks = 0;
+        while self.accumulator >= self.step && ticks < MAX_TICKS_PER_FRAME {
+            self.accumulator -= self.step;
+            ticks += 1;
+        }
+        // Drop time we could not catch up on
+        if ticks == MAX_TICKS_PER_FRAME {
+            self.accumulator = self.accumulator.min(self.step);
+        }
+        ticks
+    }
+}
@@ -5,6 +5,9 @@ use ::rand::prelude::*;
 use ::rand::rngs::StdRng;
 use ::rand::SeedableRng;
 use crate::world::objects::{WorldObject, ObjectType};
+use crate::world::ids::IdAllocator;
+use crate::app::storage::RectDef;
+use serde::{Deserialize, Serialize};
 
 /// World configuration
 pub const WORLD_WIDTH: f32 = 2000.0;
@@ -13,21 +16,54 @@ pub const STREET_WIDTH: f32 = 40.0;
 pub const BLOCK_SIZE: f32 = 200.0;
 pub const AVENUE_INTERVAL: usize = 3; // Every 3rd street is an avenue (wider)
 
+/// Size of the generated city
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum WorldSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl WorldSize {
+    pub const ALL: [WorldSize; 3] = [WorldSize::Small, WorldSize::Medium, WorldSize::Large];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WorldSize::Small => "Small",
+            WorldSize::Medium => "Medium",
+            WorldSize::Large => "Large",
+        }
+    }
+
+    /// World width and height, always whole blocks
+    pub fn dimensions(&self) -> (f32, f32) {
+        match self {
+            WorldSize::Small => (1200.0, 1200.0),
+            WorldSize::Medium => (WORLD_WIDTH, WORLD_HEIGHT),
+            WorldSize::Large => (3000.0, 3000.0),
+        }
+    }
+}
+
 /// Street segment
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Street {
+    #[serde(with = "RectDef")]
     pub rect: Rect,
     pub is_avenue: bool,
 }
 
 /// City block (area between streets)
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Block {
+    #[serde(with = "RectDef")]
     pub rect: Rect,
     pub is_park: bool,
 }
 
 /// Complete generated world
+#[derive(Clone, Serialize, Deserialize)]
 pub struct World {
     pub streets: Vec<Street>,
     pub blocks: Vec<Block>,
@@ -38,14 +74,16 @@ pub struct World {
 
 impl World {
     /// Generate a new procedural city
-    pub fn generate(seed: u64) -> Self {
+    pub fn generate(seed: u64, size: WorldSize) -> Self {
         let mut rng = StdRng::seed_from_u64(seed);
         let mut streets = Vec::new();
         let mut blocks = Vec::new();
         let mut objects = Vec::new();
+        let mut ids = IdAllocator::new();
 
-        let num_blocks_x = (WORLD_WIDTH / BLOCK_SIZE) as usize;
-        let num_blocks_y = (WORLD_HEIGHT / BLOCK_SIZE) as usize;
+        let (world_width, world_height) = size.dimensions();
+        let num_blocks_x = (world_width / BLOCK_SIZE) as usize;
+        let num_blocks_y = (world_height / BLOCK_SIZE) as usize;
 
         // Generate horizontal streets
         for i in 0..=num_blocks_y {
@@ -53,7 +91,7 @@ impl World {
             let is_avenue = i % AVENUE_INTERVAL == 0;
             let width = if is_avenue { STREET_WIDTH * 1.5 } else { STREET_WIDTH };
             streets.push(Street {
-                rect: Rect::new(0.0, y - width / 2.0, WORLD_WIDTH, width),
+                rect: Rect::new(0.0, y - width / 2.0, world_width, width),
                 is_avenue,
             });
         }
@@ -64,7 +102,7 @@ impl World {
             let is_avenue = i % AVENUE_INTERVAL == 0;
             let width = if is_avenue { STREET_WIDTH * 1.5 } else { STREET_WIDTH };
             streets.push(Street {
-                rect: Rect::new(x - width / 2.0, 0.0, width, WORLD_HEIGHT),
+                rect: Rect::new(x - width / 2.0, 0.0, width, world_height),
                 is_avenue,
             });
         }
@@ -92,7 +130,7 @@ impl World {
                         let ox = x + rng.gen::<f32>() * w;
                         let oy = y + rng.gen::<f32>() * h;
                         objects.push(WorldObject::new(
-                            ox, oy,
+                            ids.next_id(), ox, oy,
                             ObjectType::Tree,
                             &mut rng,
                         ));
@@ -103,7 +141,7 @@ impl World {
                         let ox = x + rng.gen::<f32>() * w;
                         let oy = y + rng.gen::<f32>() * h;
                         objects.push(WorldObject::new(
-                            ox, oy,
+                            ids.next_id(), ox, oy,
                             ObjectType::Bench,
                             &mut rng,
                         ));
@@ -120,6 +158,7 @@ impl World {
                         let oy = y + building_padding;
                         
                         objects.push(WorldObject::new_building(
+                            ids.next_id(),
                             ox + bw / 2.0, 
                             oy + bh / 2.0,
                             bw, bh,
@@ -140,7 +179,7 @@ impl World {
                 while x < street.rect.x + street.rect.w {
                     if rng.gen::<f32>() < 0.7 {
                         objects.push(WorldObject::new(
-                            x, street.rect.y + 5.0,
+                            ids.next_id(), x, street.rect.y + 5.0,
                             ObjectType::Lamppost,
                             &mut rng,
                         ));
@@ -153,7 +192,7 @@ impl World {
                 while y < street.rect.y + street.rect.h {
                     if rng.gen::<f32>() < 0.7 {
                         objects.push(WorldObject::new(
-                            street.rect.x + 5.0, y,
+                            ids.next_id(), street.rect.x + 5.0, y,
                             ObjectType::Lamppost,
                             &mut rng,
                         ));
@@ -177,47 +216,38 @@ impl World {
                             street.rect.y + rng.gen::<f32>() * street.rect.h,
                         )
                     };
-                    objects.push(WorldObject::new(cx, cy, ObjectType::Car, &mut rng));
+                    objects.push(WorldObject::new(ids.next_id(), cx, cy, ObjectType::Car, &mut rng));
                 }
             }
 
             // People
             let people_count = rng.gen_range(2..6);
             for _ in 0..people_count {
-                let (px, py) = if street.rect.w > street.rect.h {
-                    (
-                        street.rect.x + rng.gen::<f32>() * street.rect.w,
-                        street.rect.y + rng.gen::<f32>() * street.rect.h,
-                    )
-                } else {
-                    (
-                        street.rect.x + rng.gen::<f32>() * street.rect.w,
-                        street.rect.y + rng.gen::<f32>() * street.rect.h,
-                    )
-                };
-                objects.push(WorldObject::new(px, py, ObjectType::Person, &mut rng));
+                let px = street.rect.x + rng.gen::<f32>() * street.rect.w;
+                let py = street.rect.y + rng.gen::<f32>() * street.rect.h;
+                objects.push(WorldObject::new(ids.next_id(), px, py, ObjectType::Person, &mut rng));
             }
         }
 
-        // Add some hydrants and trash cans
-        let misc_count = 50;
+        // Add some hydrants and trash cans, 50 on a medium map
+        let misc_count = (50.0 * world_width * world_height / (WORLD_WIDTH * WORLD_HEIGHT)) as usize;
         for _ in 0..misc_count {
-            let x = rng.gen::<f32>() * WORLD_WIDTH;
-            let y = rng.gen::<f32>() * WORLD_HEIGHT;
+            let x = rng.gen::<f32>() * world_width;
+            let y = rng.gen::<f32>() * world_height;
             let obj_type = if rng.gen::<f32>() < 0.5 {
                 ObjectType::Hydrant
             } else {
                 ObjectType::TrashCan
             };
-            objects.push(WorldObject::new(x, y, obj_type, &mut rng));
+            objects.push(WorldObject::new(ids.next_id(), x, y, obj_type, &mut rng));
         }
 
         Self {
             streets,
             blocks,
             objects,
-            width: WORLD_WIDTH,
-            height: WORLD_HEIGHT,
+            width: world_width,
+            height: world_height,
         }
     }
 
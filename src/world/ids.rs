@@ -0,0 +1,20 @@
+//! Per-session id allocation for holes and world objects
+
+/// Hands out sequential ids; each session owns its own allocators
+#[derive(Clone, Default, Debug)]
+pub struct IdAllocator {
+    next: u32,
+}
+
+impl IdAllocator {
+    pub fn new() -> Self {
+        Self { next: 0 }
+    }
+
+    /// Allocate the next id
+    pub fn next_id(&mut self) -> u32 {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+}
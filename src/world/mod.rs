@@ -1,3 +1,4 @@
 pub mod gen;
 pub mod objects;
 pub mod spatial;
+pub mod ids;
@@ -2,9 +2,11 @@
 
 use macroquad::prelude::*;
 use ::rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::app::storage::ColorDef;
 
 /// Types of objects in the world
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ObjectType {
     Building,
     Car,
@@ -47,12 +49,13 @@ impl ObjectType {
 }
 
 /// Object state during capture
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ObjectState {
     /// Normal, on the ground
     Normal,
     /// Being captured (falling into hole)
     Falling {
+        captor: u32,        // Id of the hole that captured it
         progress: f32,      // 0.0 to 1.0
         target_x: f32,
         target_y: f32,
@@ -63,7 +66,7 @@ pub enum ObjectState {
 }
 
 /// A world object that can be swallowed
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct WorldObject {
     pub id: u32,
     pub x: f32,
@@ -75,23 +78,14 @@ pub struct WorldObject {
     pub obj_type: ObjectType,
     pub state: ObjectState,
     pub consumed: bool,
+    #[serde(with = "ColorDef")]
     pub color: Color,
     pub rotation: f32,
 }
 
-static mut NEXT_ID: u32 = 0;
-
-fn get_next_id() -> u32 {
-    unsafe {
-        let id = NEXT_ID;
-        NEXT_ID += 1;
-        id
-    }
-}
-
 impl WorldObject {
     /// Create a new world object
-    pub fn new(x: f32, y: f32, obj_type: ObjectType, rng: &mut impl Rng) -> Self {
+    pub fn new(id: u32, x: f32, y: f32, obj_type: ObjectType, rng: &mut impl Rng) -> Self {
         let base_size = obj_type.base_size();
         let size_variation = rng.gen_range(0.8..1.2);
         let size = base_size * size_variation;
@@ -107,7 +101,7 @@ impl WorldObject {
         );
 
         Self {
-            id: get_next_id(),
+            id,
             x, y,
             width: size,
             height: size,
@@ -122,7 +116,7 @@ impl WorldObject {
     }
 
     /// Create a building with specific dimensions
-    pub fn new_building(x: f32, y: f32, width: f32, height: f32, rng: &mut impl Rng) -> Self {
+    pub fn new_building(id: u32, x: f32, y: f32, width: f32, height: f32, rng: &mut impl Rng) -> Self {
         let size = (width + height) / 2.0;
         
         // Building colors with variation
@@ -130,7 +124,7 @@ impl WorldObject {
         let color = Color::new(gray, gray, gray + 0.05, 1.0);
 
         Self {
-            id: get_next_id(),
+            id,
             x, y,
             width,
             height,
@@ -150,9 +144,10 @@ impl WorldObject {
         self.size <= hole_radius * K_FIT
     }
 
-    /// Start falling animation toward the hole
-    pub fn start_falling(&mut self, hole_x: f32, hole_y: f32) {
+    /// Start falling animation toward the hole that captured it
+    pub fn start_falling(&mut self, captor: u32, hole_x: f32, hole_y: f32) {
         self.state = ObjectState::Falling {
+            captor,
             progress: 0.0,
             target_x: hole_x,
             target_y: hole_y,
@@ -162,7 +157,7 @@ impl WorldObject {
 
     /// Update falling animation, returns true when complete
     pub fn update_falling(&mut self, dt: f32) -> bool {
-        if let ObjectState::Falling { progress, target_x, target_y, rotation } = &mut self.state {
+        if let ObjectState::Falling { progress, target_x, target_y, rotation, .. } = &mut self.state {
             let fall_speed = 3.0; // Complete in ~0.33 seconds
             *progress += dt * fall_speed;
             *rotation += dt * 15.0; // Spin while falling
@@ -183,6 +178,22 @@ impl WorldObject {
         false
     }
 
+    /// Hole that captured this object, while it is falling
+    pub fn captor(&self) -> Option<u32> {
+        match self.state {
+            ObjectState::Falling { captor, .. } => Some(captor),
+            _ => None,
+        }
+    }
+
+    /// Move the point the object is falling toward (the captor moved)
+    pub fn retarget_falling(&mut self, x: f32, y: f32) {
+        if let ObjectState::Falling { target_x, target_y, .. } = &mut self.state {
+            *target_x = x;
+            *target_y = y;
+        }
+    }
+
     /// Get visual scale based on state
     pub fn get_visual_scale(&self) -> f32 {
         match &self.state {
@@ -3,12 +3,13 @@
 use crate::world::objects::WorldObject;
 use macroquad::prelude::*;
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 
 /// Cell size for spatial grid
 pub const CELL_SIZE: f32 = 100.0;
 
 /// Grid cell coordinate
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct CellCoord {
     pub x: i32,
     pub y: i32,
@@ -24,6 +25,7 @@ impl CellCoord {
 }
 
 /// Spatial grid for efficient lookups
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SpatialGrid {
     /// Maps cell coordinates to object indices
     cells: HashMap<CellCoord, Vec<usize>>,
@@ -55,7 +57,7 @@ impl SpatialGrid {
             for cx in min_cell.x..=max_cell.x {
                 for cy in min_cell.y..=max_cell.y {
                     let coord = CellCoord { x: cx, y: cy };
-                    self.cells.entry(coord).or_insert_with(Vec::new).push(idx);
+                    self.cells.entry(coord).or_default().push(idx);
                 }
             }
         }